@@ -0,0 +1,299 @@
+use super::input::{LeftRightPosition, PaddleRow};
+
+/// Highest reading of the 12-bit ADC.
+const ADC_MAX: u16 = 4095;
+/// Readings the stick has to travel each way from the centre for a calibration to be kept.
+const MIN_TRAVEL: u16 = 256;
+
+/// Raw ADC readings with a controller at both ends and in the middle of its travel.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Calibration {
+    min: u16,
+    center: u16,
+    max: u16,
+}
+
+impl Calibration {
+    /// The full range of the ADC with the centre in the middle, for controllers that were never
+    /// calibrated.
+    pub const UNCALIBRATED: Calibration = Calibration {
+        min: 0,
+        center: ADC_MAX.div_ceil(2),
+        max: ADC_MAX,
+    };
+
+    /// `None` unless the centre lies at least `MIN_TRAVEL` from both ends.
+    pub fn new(min: u16, center: u16, max: u16) -> Option<Calibration> {
+        let valid =
+            min.saturating_add(MIN_TRAVEL) <= center && center.saturating_add(MIN_TRAVEL) <= max;
+        match valid {
+            true => Some(Calibration { min, center, max }),
+            false => None,
+        }
+    }
+
+    /// Whether `sample` lies between the ends, or at most `margin` past them.
+    pub fn contains(&self, sample: u16, margin: u16) -> bool {
+        self.min.saturating_sub(margin) <= sample && sample <= self.max.saturating_add(margin)
+    }
+
+    /// Maps a reading to 0-100%, the centre to 50%. Each half of the travel is scaled on its own,
+    /// so a stick whose centre is off still rests at 50%. Readings past the ends count as the ends.
+    pub fn percentage(&self, sample: u16) -> u8 {
+        let sample = sample.clamp(self.min, self.max) as u32;
+        let (min, center, max) = (self.min as u32, self.center as u32, self.max as u32);
+        let percentage = match sample < center {
+            true => (sample - min) * 50 / (center - min),
+            false => 50 + (sample - center) * 50 / (max - center),
+        };
+        percentage as u8
+    }
+}
+
+impl Default for Calibration {
+    fn default() -> Self {
+        Calibration::UNCALIBRATED
+    }
+}
+
+/// Collects one controller's readings while its player follows the calibration steps.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CalibrationRecorder {
+    center_sum: u32,
+    center_samples: u32,
+    min: u16,
+    max: u16,
+}
+
+impl CalibrationRecorder {
+    pub fn new() -> CalibrationRecorder {
+        CalibrationRecorder {
+            center_sum: 0,
+            center_samples: 0,
+            min: u16::MAX,
+            max: 0,
+        }
+    }
+
+    /// A reading with the stick left in the middle, the centre is the average of them.
+    pub fn record_center(&mut self, sample: u16) {
+        self.center_sum += sample as u32;
+        self.center_samples += 1;
+    }
+
+    /// A reading while the stick is moved to both ends, the lowest and highest are kept.
+    pub fn record_travel(&mut self, sample: u16) {
+        self.min = self.min.min(sample);
+        self.max = self.max.max(sample);
+    }
+
+    /// `None` if the centre was never recorded or the stick didn't travel far enough.
+    pub fn finish(&self) -> Option<Calibration> {
+        match self.center_samples {
+            0 => None,
+            samples => Calibration::new(self.min, (self.center_sum / samples) as u16, self.max),
+        }
+    }
+}
+
+impl Default for CalibrationRecorder {
+    fn default() -> Self {
+        CalibrationRecorder::new()
+    }
+}
+
+/// Every player's calibration, as kept in flash.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct StoredCalibration {
+    pub left: Calibration,
+    pub right: Calibration,
+    /// Only four-player games have top and bottom players.
+    pub top: Calibration,
+    pub bottom: Calibration,
+    /// Only doubles games have back players.
+    pub left_back: Calibration,
+    pub right_back: Calibration,
+    pub top_back: Calibration,
+    pub bottom_back: Calibration,
+}
+
+impl StoredCalibration {
+    pub const BYTES: usize = 52;
+    /// Marks saved calibration. It comes last, so a save that was cut short isn't loaded.
+    const MAGIC: u32 = 0x4c41_4350;
+    const MAGIC_OFFSET: usize = StoredCalibration::BYTES - 4;
+
+    /// The calibration of the control for the paddle in `row` on `side`.
+    pub fn get(&self, side: &LeftRightPosition, row: &PaddleRow) -> &Calibration {
+        match (side, row) {
+            (LeftRightPosition::Left, PaddleRow::Front) => &self.left,
+            (LeftRightPosition::Right, PaddleRow::Front) => &self.right,
+            (LeftRightPosition::Top, PaddleRow::Front) => &self.top,
+            (LeftRightPosition::Bottom, PaddleRow::Front) => &self.bottom,
+            (LeftRightPosition::Left, PaddleRow::Back) => &self.left_back,
+            (LeftRightPosition::Right, PaddleRow::Back) => &self.right_back,
+            (LeftRightPosition::Top, PaddleRow::Back) => &self.top_back,
+            (LeftRightPosition::Bottom, PaddleRow::Back) => &self.bottom_back,
+        }
+    }
+
+    pub fn get_mut(&mut self, side: &LeftRightPosition, row: &PaddleRow) -> &mut Calibration {
+        match (side, row) {
+            (LeftRightPosition::Left, PaddleRow::Front) => &mut self.left,
+            (LeftRightPosition::Right, PaddleRow::Front) => &mut self.right,
+            (LeftRightPosition::Top, PaddleRow::Front) => &mut self.top,
+            (LeftRightPosition::Bottom, PaddleRow::Front) => &mut self.bottom,
+            (LeftRightPosition::Left, PaddleRow::Back) => &mut self.left_back,
+            (LeftRightPosition::Right, PaddleRow::Back) => &mut self.right_back,
+            (LeftRightPosition::Top, PaddleRow::Back) => &mut self.top_back,
+            (LeftRightPosition::Bottom, PaddleRow::Back) => &mut self.bottom_back,
+        }
+    }
+
+    pub fn to_bytes(&self) -> [u8; StoredCalibration::BYTES] {
+        let mut bytes = [0; StoredCalibration::BYTES];
+        let values = [
+            self.left,
+            self.right,
+            self.top,
+            self.bottom,
+            self.left_back,
+            self.right_back,
+            self.top_back,
+            self.bottom_back,
+        ]
+        .into_iter()
+        .flat_map(|calibration| [calibration.min, calibration.center, calibration.max]);
+        for (chunk, value) in bytes.chunks_exact_mut(2).zip(values) {
+            chunk.copy_from_slice(&value.to_le_bytes());
+        }
+        bytes[StoredCalibration::MAGIC_OFFSET..]
+            .copy_from_slice(&StoredCalibration::MAGIC.to_le_bytes());
+        bytes
+    }
+
+    /// `None` for anything `to_bytes` didn't write, e.g. erased flash.
+    pub fn from_bytes(bytes: &[u8]) -> Option<StoredCalibration> {
+        if bytes.len() < StoredCalibration::BYTES {
+            return None;
+        }
+        let word = |index: usize| u16::from_le_bytes([bytes[2 * index], bytes[2 * index + 1]]);
+        let magic = &bytes[StoredCalibration::MAGIC_OFFSET..StoredCalibration::BYTES];
+        if magic != StoredCalibration::MAGIC.to_le_bytes() {
+            return None;
+        }
+        let calibration =
+            |first: usize| Calibration::new(word(first), word(first + 1), word(first + 2));
+        Some(StoredCalibration {
+            left: calibration(0)?,
+            right: calibration(3)?,
+            top: calibration(6)?,
+            bottom: calibration(9)?,
+            left_back: calibration(12)?,
+            right_back: calibration(15)?,
+            top_back: calibration(18)?,
+            bottom_back: calibration(21)?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn uncalibrated_spans_whole_adc_range() {
+        let calibration = Calibration::UNCALIBRATED;
+        assert_eq!(calibration.percentage(0), 0);
+        assert_eq!(calibration.percentage(2048), 50);
+        assert_eq!(calibration.percentage(ADC_MAX), 100);
+    }
+
+    #[test]
+    fn each_half_is_scaled_on_its_own() {
+        let calibration = Calibration::new(1000, 1500, 3500).unwrap();
+        assert_eq!(calibration.percentage(1250), 25);
+        assert_eq!(calibration.percentage(1500), 50);
+        assert_eq!(calibration.percentage(2500), 75);
+    }
+
+    #[test]
+    fn readings_past_the_ends_are_clamped() {
+        let calibration = Calibration::new(1000, 2000, 3000).unwrap();
+        assert_eq!(calibration.percentage(200), 0);
+        assert_eq!(calibration.percentage(4000), 100);
+    }
+
+    #[test]
+    fn contains_allows_margin_past_the_ends() {
+        let calibration = Calibration::new(1000, 2000, 3000).unwrap();
+        assert!(calibration.contains(950, 50));
+        assert!(!calibration.contains(949, 50));
+        assert!(calibration.contains(3050, 50));
+        assert!(!calibration.contains(3051, 50));
+        assert!(Calibration::UNCALIBRATED.contains(0, 0));
+    }
+
+    #[test]
+    fn too_little_travel_is_rejected() {
+        assert_eq!(Calibration::new(1000, 1100, 3000), None);
+        assert_eq!(Calibration::new(1000, 2900, 3000), None);
+        assert_eq!(Calibration::new(0, 0, 0), None);
+    }
+
+    #[test]
+    fn recorder_averages_center_and_keeps_ends() {
+        let mut recorder = CalibrationRecorder::new();
+        assert_eq!(recorder.finish(), None);
+        recorder.record_center(1990);
+        recorder.record_center(2010);
+        for sample in [2000, 400, 1200, 3700, 3600] {
+            recorder.record_travel(sample);
+        }
+        assert_eq!(recorder.finish(), Calibration::new(400, 2000, 3700));
+    }
+
+    #[test]
+    fn recorder_without_travel_fails() {
+        let mut recorder = CalibrationRecorder::new();
+        recorder.record_center(2000);
+        recorder.record_travel(2000);
+        assert_eq!(recorder.finish(), None);
+    }
+
+    #[test]
+    fn stored_calibration_survives_round_trip() {
+        let stored = StoredCalibration {
+            left: Calibration::new(100, 2000, 4000).unwrap(),
+            right: Calibration::new(300, 1800, 3900).unwrap(),
+            top: Calibration::new(0, 2048, 4095).unwrap(),
+            bottom: Calibration::new(500, 2500, 3500).unwrap(),
+            left_back: Calibration::new(200, 2100, 3800).unwrap(),
+            ..StoredCalibration::default()
+        };
+        assert_eq!(
+            StoredCalibration::from_bytes(&stored.to_bytes()),
+            Some(stored)
+        );
+        assert_eq!(
+            stored.get(&LeftRightPosition::Right, &PaddleRow::Front),
+            &Calibration::new(300, 1800, 3900).unwrap()
+        );
+        assert_eq!(
+            stored.get(&LeftRightPosition::Left, &PaddleRow::Back),
+            &Calibration::new(200, 2100, 3800).unwrap()
+        );
+    }
+
+    #[test]
+    fn erased_or_damaged_flash_is_not_loaded() {
+        assert_eq!(
+            StoredCalibration::from_bytes(&[0xff; StoredCalibration::BYTES]),
+            None
+        );
+        assert_eq!(StoredCalibration::from_bytes(&[0; 8]), None);
+        let mut bytes = StoredCalibration::default().to_bytes();
+        bytes[0..2].copy_from_slice(&3000u16.to_le_bytes());
+        assert_eq!(StoredCalibration::from_bytes(&bytes), None);
+    }
+}
@@ -0,0 +1,444 @@
+use embedded_graphics::primitives::Rectangle;
+
+use super::game_objects::ball::Ball;
+use super::game_objects::paddle::Paddle;
+use heapless::Vec;
+
+use super::game_objects::{transposed_area, Game, PADDLES_PER_SIDE};
+use super::input::{InpuDirection, InputError, LeftRightPosition, PaddleInput, UserInteraction};
+use super::physics::Fixed;
+use super::random::{Random, XorShift32};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Difficulty {
+    /// Number of frames between each time the computer looks at the ball.
+    pub reaction_delay: u32,
+    /// Number of wall bounces the computer can foresee. If the ball will bounce more times before
+    /// reaching the paddle the computer just follows the ball.
+    pub prediction_depth: u32,
+    /// Largest distance, in pixels, the computer may misjudge where the ball will arrive.
+    pub max_error: u16,
+}
+
+impl Difficulty {
+    pub const EASY: Difficulty = Difficulty {
+        reaction_delay: 12,
+        prediction_depth: 0,
+        max_error: 20,
+    };
+    pub const MEDIUM: Difficulty = Difficulty {
+        reaction_delay: 6,
+        prediction_depth: 1,
+        max_error: 10,
+    };
+    pub const HARD: Difficulty = Difficulty {
+        reaction_delay: 2,
+        prediction_depth: 3,
+        max_error: 2,
+    };
+}
+
+/// A computer controlled paddle. Plugs in wherever a `UserInteraction` is expected, see
+/// `SplitInputs`. Its misjudgements come from `R`. In doubles it plays both paddles, each one
+/// heading for where the ball will reach it.
+pub struct ComputerPlayer<R = XorShift32> {
+    side: LeftRightPosition,
+    difficulty: Difficulty,
+    /// Front paddle first, like `PaddleRow::ALL`.
+    aims: [PaddleAim; PADDLES_PER_SIDE],
+    dead_zone: i32,
+    frames_until_reaction: u32,
+    random: R,
+}
+
+/// Where one of the computer's paddles is and where it is heading.
+#[derive(Clone, Copy, Debug, Default)]
+struct PaddleAim {
+    target_height: Option<i32>,
+    paddle_center: i32,
+}
+
+impl ComputerPlayer {
+    pub fn new(side: LeftRightPosition, difficulty: Difficulty) -> ComputerPlayer {
+        ComputerPlayer::with_random(side, difficulty, XorShift32::default())
+    }
+}
+
+impl<R: Random> ComputerPlayer<R> {
+    pub fn with_random(
+        side: LeftRightPosition,
+        difficulty: Difficulty,
+        random: R,
+    ) -> ComputerPlayer<R> {
+        ComputerPlayer {
+            side,
+            difficulty,
+            aims: [PaddleAim::default(); PADDLES_PER_SIDE],
+            dead_zone: 0,
+            frames_until_reaction: 0,
+            random,
+        }
+    }
+
+    pub fn set_difficulty(&mut self, difficulty: Difficulty) {
+        self.difficulty = difficulty;
+    }
+
+    /// `has_walls` is false when the walls along the paddle's way are goals, in four-player games.
+    fn observe(&mut self, ball: &Ball, paddles: &[Paddle], court: &Rectangle, has_walls: bool) {
+        for (aim, paddle) in self.aims.iter_mut().zip(paddles) {
+            let half_paddle = (paddle.y_size / 2) as i32;
+            aim.paddle_center = paddle.top_left_pos.y.round() + half_paddle;
+            // Don't chase the last few pixels, the paddle would just jitter around the target.
+            self.dead_zone = half_paddle / 2;
+        }
+
+        if self.frames_until_reaction > 0 {
+            self.frames_until_reaction -= 1;
+            return;
+        }
+        self.frames_until_reaction = self.difficulty.reaction_delay;
+        let is_ball_approaching = self.is_ball_approaching(ball);
+        for (index, paddle) in paddles.iter().enumerate().take(PADDLES_PER_SIDE) {
+            let target_height = match is_ball_approaching {
+                true => {
+                    self.predict_arrival_height(ball, paddle, court, has_walls) + self.next_error()
+                }
+                false => court.center().y,
+            };
+            self.aims[index].target_height = Some(target_height);
+        }
+    }
+
+    fn direction_towards_target(&self, aim: &PaddleAim) -> InpuDirection {
+        match aim.target_height {
+            Some(target) if target < aim.paddle_center - self.dead_zone => InpuDirection::Up,
+            Some(target) if target > aim.paddle_center + self.dead_zone => InpuDirection::Down,
+            _ => InpuDirection::Stay,
+        }
+    }
+
+    fn is_ball_approaching(&self, ball: &Ball) -> bool {
+        match self.side {
+            LeftRightPosition::Left | LeftRightPosition::Top => ball.velocity.vx < Fixed::ZERO,
+            LeftRightPosition::Right | LeftRightPosition::Bottom => ball.velocity.vx > Fixed::ZERO,
+        }
+    }
+
+    /// Height at which the ball reaches the paddle, following it through at most
+    /// `prediction_depth` bounces against the top and bottom walls. Without walls the ball never
+    /// comes back, the paddle only gets as close as it can.
+    fn predict_arrival_height(
+        &self,
+        ball: &Ball,
+        paddle: &Paddle,
+        court: &Rectangle,
+        has_walls: bool,
+    ) -> i32 {
+        let paddle_front = match self.side {
+            LeftRightPosition::Left | LeftRightPosition::Top => {
+                paddle.top_left_pos.x + Fixed::from_int(paddle.x_size as i32)
+            }
+            LeftRightPosition::Right | LeftRightPosition::Bottom => paddle.top_left_pos.x,
+        };
+        let distance = paddle_front - ball.position.x;
+        // Height the ball would reach if there were no walls.
+        let unbounded_height = (ball.position.y - Fixed::from_int(court.top_left.y)
+            + ball.velocity.vy * distance / ball.velocity.vx)
+            .round();
+
+        let ball_height = ball.position.y.round();
+        let court_height = court.bottom_right().unwrap().y - court.top_left.y;
+        if court_height <= 0 {
+            return ball_height;
+        }
+        if !has_walls {
+            return court.top_left.y + unbounded_height.clamp(0, court_height);
+        }
+        let bounces = unbounded_height.div_euclid(court_height).unsigned_abs();
+        if bounces > self.difficulty.prediction_depth {
+            return ball_height;
+        }
+        // Every bounce mirrors the path, so fold the unbounded height back into the court.
+        let folded_height = unbounded_height.rem_euclid(2 * court_height);
+        let height = match folded_height > court_height {
+            true => 2 * court_height - folded_height,
+            false => folded_height,
+        };
+        court.top_left.y + height
+    }
+
+    fn next_error(&mut self) -> i32 {
+        self.random.next_between(self.difficulty.max_error.into()) as i32
+    }
+}
+
+impl<R: Random> UserInteraction for ComputerPlayer<R> {
+    fn get_input_direction(
+        &mut self,
+        _user_position: &LeftRightPosition,
+    ) -> Result<InpuDirection, InputError> {
+        Ok(self.direction_towards_target(&self.aims[0]))
+    }
+    fn get_back_paddle_input(
+        &mut self,
+        _user_position: &LeftRightPosition,
+    ) -> Result<PaddleInput, InputError> {
+        Ok(PaddleInput::Direction(
+            self.direction_towards_target(&self.aims[1]),
+        ))
+    }
+    fn observe_game(&mut self, game: &Game) {
+        let (ball, court) = (game.get_ball(), game.get_screen_dimensions());
+        let has_walls = !game.has_four_players();
+        let paddles = game.get_paddles(&self.side);
+        match self.side {
+            _ if paddles.is_empty() => {}
+            // Mirrored along the diagonal the top and bottom paddles play like the left and right
+            // ones.
+            LeftRightPosition::Top | LeftRightPosition::Bottom => {
+                let paddles: Vec<Paddle, PADDLES_PER_SIDE> =
+                    paddles.iter().map(Paddle::transposed).collect();
+                self.observe(
+                    &ball.transposed(),
+                    &paddles,
+                    &transposed_area(&court),
+                    has_walls,
+                )
+            }
+            LeftRightPosition::Left | LeftRightPosition::Right => {
+                self.observe(&ball, paddles, &court, has_walls)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use embedded_graphics::prelude::{Point, Size};
+
+    use super::*;
+    use crate::game_objects::GameBuilder;
+    use crate::physics::{FixedPoint, Velocity};
+
+    const PERFECT: Difficulty = Difficulty {
+        reaction_delay: 0,
+        prediction_depth: 10,
+        max_error: 0,
+    };
+
+    fn court() -> Rectangle {
+        Rectangle {
+            top_left: Point { x: 0, y: 0 },
+            size: Size {
+                width: 160,
+                height: 128,
+            },
+        }
+    }
+
+    fn ball_at(x: i32, y: i32, vx: i32, vy: i32) -> Ball {
+        Ball {
+            position: FixedPoint::from(Point { x, y }),
+            radius: 3,
+            velocity: Velocity::from_pixels(vx, vy),
+            has_moved: false,
+        }
+    }
+
+    fn right_paddle_at(y: i32) -> Paddle {
+        paddle_at(154, y)
+    }
+
+    fn paddle_at(x: i32, y: i32) -> Paddle {
+        Paddle {
+            top_left_pos: FixedPoint::from(Point { x, y }),
+            x_size: 6,
+            y_size: 40,
+            has_moved: false,
+            y_movement: Fixed::ZERO,
+            side: LeftRightPosition::Left,
+        }
+    }
+
+    fn direction_after_observing(
+        player: &mut ComputerPlayer,
+        ball: &Ball,
+        paddle: &Paddle,
+    ) -> InpuDirection {
+        let side = player.side;
+        player.observe(ball, &[*paddle], &court(), true);
+        player.get_input_direction(&side).unwrap()
+    }
+
+    #[test]
+    fn follows_approaching_ball() {
+        let mut player = ComputerPlayer::new(LeftRightPosition::Right, PERFECT);
+        let paddle = right_paddle_at(40);
+        assert_eq!(
+            direction_after_observing(&mut player, &ball_at(100, 10, 2, 0), &paddle),
+            InpuDirection::Up
+        );
+        assert_eq!(
+            direction_after_observing(&mut player, &ball_at(100, 110, 2, 0), &paddle),
+            InpuDirection::Down
+        );
+        assert_eq!(
+            direction_after_observing(&mut player, &ball_at(100, 60, 2, 0), &paddle),
+            InpuDirection::Stay
+        );
+    }
+
+    #[test]
+    fn predicts_straight_path() {
+        let player = ComputerPlayer::new(LeftRightPosition::Right, PERFECT);
+        let ball = ball_at(54, 20, 2, 1);
+        assert_eq!(
+            player.predict_arrival_height(&ball, &right_paddle_at(0), &court(), true),
+            70
+        );
+    }
+
+    #[test]
+    fn predicts_bounce_against_bottom_wall() {
+        let player = ComputerPlayer::new(LeftRightPosition::Right, PERFECT);
+        let ball = ball_at(54, 100, 1, 1);
+        // Reaches the bottom wall (127) after 27 pixels and travels 73 back up.
+        assert_eq!(
+            player.predict_arrival_height(&ball, &right_paddle_at(0), &court(), true),
+            54
+        );
+    }
+
+    #[test]
+    fn predicts_no_bounce_against_goal() {
+        let player = ComputerPlayer::new(LeftRightPosition::Right, PERFECT);
+        let ball = ball_at(54, 100, 1, 1);
+        // The ball leaves through the bottom goal, the paddle waits as low as it gets.
+        assert_eq!(
+            player.predict_arrival_height(&ball, &right_paddle_at(0), &court(), false),
+            127
+        );
+    }
+
+    #[test]
+    fn predicts_bounce_for_left_side() {
+        let player = ComputerPlayer::new(LeftRightPosition::Left, PERFECT);
+        let paddle = Paddle {
+            top_left_pos: FixedPoint::default(),
+            x_size: 6,
+            y_size: 40,
+            has_moved: false,
+            y_movement: Fixed::ZERO,
+            side: LeftRightPosition::Left,
+        };
+        let ball = ball_at(56, 20, -1, -1);
+        assert_eq!(
+            player.predict_arrival_height(&ball, &paddle, &court(), true),
+            30
+        );
+    }
+
+    #[test]
+    fn follows_ball_when_bounces_are_too_many_to_foresee() {
+        let difficulty = Difficulty {
+            prediction_depth: 0,
+            ..PERFECT
+        };
+        let player = ComputerPlayer::new(LeftRightPosition::Right, difficulty);
+        let ball = ball_at(54, 100, 1, 1);
+        assert_eq!(
+            player.predict_arrival_height(&ball, &right_paddle_at(0), &court(), true),
+            100
+        );
+    }
+
+    #[test]
+    fn aims_each_doubles_paddle_where_the_ball_reaches_it() {
+        let mut player = ComputerPlayer::new(LeftRightPosition::Right, PERFECT);
+        let paddles = [paddle_at(114, 0), right_paddle_at(0)];
+        player.observe(&ball_at(100, 10, 2, 1), &paddles, &court(), true);
+        // The ball reaches the front paddle at 17 and the back one at 64.
+        assert_eq!(
+            player.get_input_direction(&LeftRightPosition::Right),
+            Ok(InpuDirection::Stay)
+        );
+        assert_eq!(
+            player.get_back_paddle_input(&LeftRightPosition::Right),
+            Ok(PaddleInput::Direction(InpuDirection::Down))
+        );
+    }
+
+    #[test]
+    fn returns_to_center_when_ball_moves_away() {
+        let mut player = ComputerPlayer::new(LeftRightPosition::Right, PERFECT);
+        assert_eq!(
+            direction_after_observing(&mut player, &ball_at(100, 10, -2, 0), &right_paddle_at(0)),
+            InpuDirection::Down
+        );
+    }
+
+    #[test]
+    fn reacts_only_after_delay() {
+        let difficulty = Difficulty {
+            reaction_delay: 2,
+            ..PERFECT
+        };
+        let mut player = ComputerPlayer::new(LeftRightPosition::Right, difficulty);
+        let paddle = right_paddle_at(40);
+        assert_eq!(
+            direction_after_observing(&mut player, &ball_at(100, 60, 2, 0), &paddle),
+            InpuDirection::Stay
+        );
+        for _ in 0..2 {
+            assert_eq!(
+                direction_after_observing(&mut player, &ball_at(100, 110, 2, 0), &paddle),
+                InpuDirection::Stay
+            );
+        }
+        assert_eq!(
+            direction_after_observing(&mut player, &ball_at(100, 110, 2, 0), &paddle),
+            InpuDirection::Down
+        );
+    }
+
+    #[test]
+    fn plays_top_paddle_sideways() {
+        let game = GameBuilder::new(160, 128)
+            .paddle_size(Size {
+                width: 6,
+                height: 40,
+            })
+            .initial_ball_velocity(Velocity::from_pixels(1, -2))
+            .four_players(true)
+            .build();
+        // The ball is still in the top left corner, left of the paddle, and heading up.
+        let mut player = ComputerPlayer::new(LeftRightPosition::Top, PERFECT);
+        player.observe_game(&game);
+        assert_eq!(
+            player.get_input_direction(&LeftRightPosition::Top),
+            Ok(InpuDirection::Up)
+        );
+    }
+
+    #[test]
+    fn stays_without_paddle() {
+        let game = GameBuilder::new(160, 128).build();
+        let mut player = ComputerPlayer::new(LeftRightPosition::Top, PERFECT);
+        player.observe_game(&game);
+        assert_eq!(
+            player.get_input_direction(&LeftRightPosition::Top),
+            Ok(InpuDirection::Stay)
+        );
+    }
+
+    #[test]
+    fn error_stays_within_limit() {
+        let mut player = ComputerPlayer::new(LeftRightPosition::Right, Difficulty::EASY);
+        let max_error = Difficulty::EASY.max_error as i32;
+        for _ in 0..1000 {
+            let error = player.next_error();
+            assert!((-max_error..=max_error).contains(&error));
+        }
+    }
+}
@@ -0,0 +1,209 @@
+use super::calibration::Calibration;
+use super::input::InputError;
+
+/// How to tell a controller that was unplugged from one in use. An unplugged analog input
+/// floats and jumps around, or the wiper is pulled to one of the rails.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct DisconnectDetection {
+    /// Largest change between two readings a player can make, floating inputs jump further.
+    pub max_jump: u16,
+    /// How far past the calibrated ends a reading may be before it counts as stuck at a rail.
+    /// Controllers that were never calibrated reach both rails, so only jumps give them away.
+    pub rail_margin: u16,
+    /// Suspicious readings in a row before the controller counts as unplugged.
+    pub unplug_samples: u8,
+    /// Good readings in a row before an unplugged controller counts as back.
+    pub replug_samples: u8,
+}
+
+impl DisconnectDetection {
+    /// Readings once per 20 ms frame: a tenth of a second of noise and the game stops, half a
+    /// second of steady readings and it goes on.
+    pub const DEFAULT: DisconnectDetection = DisconnectDetection {
+        max_jump: 1500,
+        rail_margin: 200,
+        unplug_samples: 5,
+        replug_samples: 25,
+    };
+    /// Never counts a controller as unplugged.
+    pub const OFF: DisconnectDetection = DisconnectDetection {
+        max_jump: u16::MAX,
+        rail_margin: u16::MAX,
+        ..DisconnectDetection::DEFAULT
+    };
+}
+
+impl Default for DisconnectDetection {
+    fn default() -> Self {
+        DisconnectDetection::DEFAULT
+    }
+}
+
+/// Watches one controller's raw readings for signs that it was unplugged.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ConnectionMonitor {
+    detection: DisconnectDetection,
+    last_sample: Option<u16>,
+    /// Readings in a row that disagree with `connected`.
+    changed_samples: u8,
+    connected: bool,
+}
+
+impl ConnectionMonitor {
+    pub fn new(detection: DisconnectDetection) -> ConnectionMonitor {
+        ConnectionMonitor {
+            detection,
+            last_sample: None,
+            changed_samples: 0,
+            connected: true,
+        }
+    }
+
+    pub fn is_connected(&self) -> bool {
+        self.connected
+    }
+
+    /// Passes `sample` on while the controller counts as plugged in.
+    pub fn check(&mut self, sample: u16, calibration: &Calibration) -> Result<u16, InputError> {
+        self.check_batch(&[sample], calibration).map(|_| sample)
+    }
+
+    /// Like `check`, for all raw readings taken since the last check. They count as one reading
+    /// towards `unplug_samples` and `replug_samples`, which looks suspicious if any of them does.
+    /// Filtered readings would hide the noise of a floating input.
+    pub fn check_batch(
+        &mut self,
+        samples: &[u16],
+        calibration: &Calibration,
+    ) -> Result<(), InputError> {
+        let mut looks_connected = true;
+        for sample in samples {
+            let jump = self
+                .last_sample
+                .map_or(0, |last_sample| last_sample.abs_diff(*sample));
+            self.last_sample = Some(*sample);
+            looks_connected &= jump <= self.detection.max_jump
+                && calibration.contains(*sample, self.detection.rail_margin);
+        }
+
+        match looks_connected == self.connected {
+            true => self.changed_samples = 0,
+            false => {
+                self.changed_samples += 1;
+                let needed = match self.connected {
+                    true => self.detection.unplug_samples,
+                    false => self.detection.replug_samples,
+                };
+                if self.changed_samples >= needed {
+                    self.connected = looks_connected;
+                    self.changed_samples = 0;
+                }
+            }
+        }
+        match self.connected {
+            true => Ok(()),
+            false => Err(InputError::Disconnected),
+        }
+    }
+}
+
+impl Default for ConnectionMonitor {
+    fn default() -> Self {
+        ConnectionMonitor::new(DisconnectDetection::DEFAULT)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DETECTION: DisconnectDetection = DisconnectDetection {
+        max_jump: 1000,
+        rail_margin: 100,
+        unplug_samples: 3,
+        replug_samples: 4,
+    };
+
+    fn calibration() -> Calibration {
+        Calibration::new(500, 2000, 3500).unwrap()
+    }
+
+    fn check_all(monitor: &mut ConnectionMonitor, samples: &[u16]) -> Result<u16, InputError> {
+        let mut result = Err(InputError::Disconnected);
+        for sample in samples {
+            result = monitor.check(*sample, &calibration());
+        }
+        result
+    }
+
+    #[test]
+    fn steady_readings_pass_through() {
+        let mut monitor = ConnectionMonitor::new(DETECTION);
+        assert_eq!(check_all(&mut monitor, &[2000, 2500, 3400, 3000]), Ok(3000));
+        assert!(monitor.is_connected());
+    }
+
+    #[test]
+    fn noise_counts_as_unplugged() {
+        let mut monitor = ConnectionMonitor::new(DETECTION);
+        assert_eq!(check_all(&mut monitor, &[2000, 500, 3500]), Ok(3500));
+        assert_eq!(
+            check_all(&mut monitor, &[600]),
+            Err(InputError::Disconnected)
+        );
+        assert!(!monitor.is_connected());
+    }
+
+    #[test]
+    fn readings_at_a_rail_count_as_unplugged() {
+        let mut monitor = ConnectionMonitor::new(DETECTION);
+        assert_eq!(
+            check_all(&mut monitor, &[4095, 4095, 4095]),
+            Err(InputError::Disconnected)
+        );
+        // Within the margin is still the end of the stick's travel.
+        let mut monitor = ConnectionMonitor::new(DETECTION);
+        assert_eq!(check_all(&mut monitor, &[3550, 3550, 3550]), Ok(3550));
+    }
+
+    #[test]
+    fn steady_readings_bring_controller_back() {
+        let mut monitor = ConnectionMonitor::new(DETECTION);
+        assert_eq!(
+            check_all(&mut monitor, &[0, 0, 0]),
+            Err(InputError::Disconnected)
+        );
+        // Plugging back in is a jump too, the steady readings count from there.
+        assert_eq!(
+            check_all(&mut monitor, &[2000, 2000, 2000, 2000]),
+            Err(InputError::Disconnected)
+        );
+        assert_eq!(check_all(&mut monitor, &[2000]), Ok(2000));
+    }
+
+    #[test]
+    fn noise_within_batches_counts_as_unplugged() {
+        let mut monitor = ConnectionMonitor::new(DETECTION);
+        assert_eq!(
+            monitor.check_batch(&[2000, 2010, 1990], &calibration()),
+            Ok(())
+        );
+        // Each batch averages out to the middle, the readings in it don't.
+        let noise = [600, 3400, 600, 3400];
+        assert_eq!(monitor.check_batch(&noise, &calibration()), Ok(()));
+        assert_eq!(monitor.check_batch(&noise, &calibration()), Ok(()));
+        assert_eq!(
+            monitor.check_batch(&noise, &calibration()),
+            Err(InputError::Disconnected)
+        );
+    }
+
+    #[test]
+    fn detection_can_be_turned_off() {
+        let mut monitor = ConnectionMonitor::new(DisconnectDetection::OFF);
+        assert_eq!(
+            check_all(&mut monitor, &[0, 4095, 0, 4095, 0, 4095]),
+            Ok(4095)
+        );
+    }
+}
@@ -0,0 +1,122 @@
+use super::physics::Fixed;
+
+/// Most readings in a batch that are looked at, older ones are left out.
+pub const MAX_BATCH: usize = 16;
+
+/// What to make of one batch of readings.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum BatchFilter {
+    /// The newest reading.
+    Latest,
+    /// The average, evens out noise.
+    Mean,
+    /// The middle reading, single spikes don't move it at all.
+    Median,
+}
+
+/// Cleans up a controller's ADC readings, which come in batches: each batch is boiled down to
+/// one reading, followed by exponential smoothing across batches.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SampleFilter {
+    batch: BatchFilter,
+    /// Share of each new batch in the result, `Fixed::ONE` turns smoothing off.
+    new_batch_weight: Fixed,
+    smoothed: Option<Fixed>,
+}
+
+impl SampleFilter {
+    /// Passes the newest reading on as is.
+    pub const NONE: SampleFilter = SampleFilter::new(BatchFilter::Latest, Fixed::ONE);
+
+    pub const fn new(batch: BatchFilter, new_batch_weight: Fixed) -> SampleFilter {
+        SampleFilter {
+            batch,
+            new_batch_weight,
+            smoothed: None,
+        }
+    }
+
+    /// An empty batch gives the last result again.
+    pub fn filter(&mut self, samples: &[u16]) -> u16 {
+        let samples = &samples[samples.len().saturating_sub(MAX_BATCH)..];
+        let reading = match (self.batch, samples.last()) {
+            (_, None) => return self.smoothed.map_or(0, |smoothed| smoothed.round() as u16),
+            (BatchFilter::Latest, Some(latest)) => *latest,
+            (BatchFilter::Mean, Some(_)) => {
+                let sum: u32 = samples.iter().map(|sample| *sample as u32).sum();
+                (sum / samples.len() as u32) as u16
+            }
+            (BatchFilter::Median, Some(_)) => {
+                let mut sorted = [0; MAX_BATCH];
+                let sorted = &mut sorted[..samples.len()];
+                sorted.copy_from_slice(samples);
+                sorted.sort_unstable();
+                sorted[sorted.len() / 2]
+            }
+        };
+        let reading = Fixed::from_int(reading as i32);
+        let smoothed = match self.smoothed {
+            Some(smoothed) => smoothed + (reading - smoothed) * self.new_batch_weight,
+            None => reading,
+        };
+        self.smoothed = Some(smoothed);
+        smoothed.round() as u16
+    }
+}
+
+impl Default for SampleFilter {
+    fn default() -> Self {
+        SampleFilter::NONE
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn batch_filters() {
+        let batch = [2000, 2010, 4095, 1990, 2005];
+        assert_eq!(SampleFilter::default().filter(&batch), 2005);
+        assert_eq!(
+            SampleFilter::new(BatchFilter::Mean, Fixed::ONE).filter(&batch),
+            2420
+        );
+        assert_eq!(
+            SampleFilter::new(BatchFilter::Median, Fixed::ONE).filter(&batch),
+            2005
+        );
+    }
+
+    #[test]
+    fn median_ignores_spikes() {
+        let mut filter = SampleFilter::new(BatchFilter::Median, Fixed::ONE);
+        assert_eq!(filter.filter(&[1000, 0, 1002, 4095, 1001, 1003]), 1002);
+    }
+
+    #[test]
+    fn long_batch_keeps_newest_readings() {
+        let batch: [u16; 20] = core::array::from_fn(|index| 1000 + index as u16);
+        assert_eq!(SampleFilter::default().filter(&batch), 1019);
+        assert_eq!(
+            SampleFilter::new(BatchFilter::Median, Fixed::ONE).filter(&batch),
+            1012
+        );
+    }
+
+    #[test]
+    fn batches_are_smoothed() {
+        let mut filter = SampleFilter::new(BatchFilter::Latest, Fixed::from_ratio(1, 4));
+        assert_eq!(filter.filter(&[1000]), 1000);
+        assert_eq!(filter.filter(&[2000]), 1250);
+        assert_eq!(filter.filter(&[2000]), 1438);
+    }
+
+    #[test]
+    fn empty_batch_repeats_last_result() {
+        let mut filter = SampleFilter::new(BatchFilter::Median, Fixed::ONE);
+        assert_eq!(filter.filter(&[]), 0);
+        filter.filter(&[1500, 1600, 1700]);
+        assert_eq!(filter.filter(&[]), 1600);
+    }
+}
@@ -0,0 +1,1919 @@
+pub mod ball;
+pub mod paddle;
+
+use embedded_graphics::geometry::Point;
+use embedded_graphics::geometry::Size;
+use embedded_graphics::primitives;
+use embedded_graphics::primitives::Rectangle;
+use embedded_graphics::text::Alignment;
+use heapless::String;
+use heapless::Vec;
+
+use core::fmt::Write;
+
+use ball::Ball;
+use paddle::Paddle;
+
+use super::input::InpuDirection;
+use super::input::LeftRightPosition;
+use super::input::PaddleRow;
+use super::physics::Fixed;
+use super::physics::FixedPoint;
+use super::physics::Obstacles;
+use super::physics::PaddleBounce;
+use super::physics::SpeedUp;
+use super::physics::TimeTick;
+use super::physics::Velocity;
+use super::random::Random;
+use super::random::XorShift32;
+
+const DEFAULT_POINTS_TO_WIN: u32 = 5;
+pub const TEXT_CAPACITY: usize = 16;
+/// Room for the court markings, a score and the paddles on every side, the ball and a message.
+pub const CONTENT_CAPACITY: usize = COURT_CAPACITY + SIDES + SIDES * PADDLES_PER_SIDE + 1 + 1;
+/// Every centre line dash, both borders and a goal line on every side.
+const COURT_CAPACITY: usize = CENTER_LINE_DASHES as usize + 2 + SIDES;
+/// A front and a back paddle in doubles.
+pub const PADDLES_PER_SIDE: usize = 2;
+const SIDES: usize = 4;
+const SCORE_TEXT_HEIGHT: i32 = 6;
+/// The centre line is made of this many dashes, however tall the screen is.
+const CENTER_LINE_DASHES: u32 = 16;
+const CENTER_LINE_WIDTH: u32 = 2;
+
+#[derive(Debug, PartialEq)]
+pub enum GameOver {
+    LeftWins,
+    RightWins,
+    TopWins,
+    BottomWins,
+}
+
+impl GameOver {
+    fn won_by(side: LeftRightPosition) -> GameOver {
+        match side {
+            LeftRightPosition::Left => GameOver::LeftWins,
+            LeftRightPosition::Right => GameOver::RightWins,
+            LeftRightPosition::Top => GameOver::TopWins,
+            LeftRightPosition::Bottom => GameOver::BottomWins,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum GameState {
+    Ongoing,
+    /// A side won the point, the match continues with `start_new_game`.
+    PointScored(GameOver),
+    /// A side has reached the points needed to win the match, start over with `start_new_match`.
+    Finnished(GameOver),
+}
+
+/// Who the ball is served to after a point.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum ServeDirection {
+    /// Left and right take turns, in four-player games top and bottom too.
+    Alternate,
+    /// Towards the side that lost the last point.
+    #[default]
+    TowardsLoser,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Score {
+    pub left: u32,
+    pub right: u32,
+    /// Only four-player games have top and bottom players.
+    pub top: u32,
+    pub bottom: u32,
+}
+
+pub trait GameObject {
+    fn set_position(&self, pos: Point) -> Self;
+    fn as_shapes(&self) -> Vec<ScreenObject, 2>; // Note: needlessly increasing N leads to much larger
+                                                 // vectors due to to enum size.
+    fn get_box_covering_object(&self) -> Rectangle;
+    fn is_within(&self, rectange: &Rectangle) -> bool;
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum ScreenObject {
+    /// A paddle, coloured after the wall it defends.
+    Paddle(primitives::Rectangle, LeftRightPosition),
+    Circle(primitives::Circle),
+    Text(ScreenText),
+    /// Part of the court decoration, the ball and paddles pass over it.
+    CourtMarking(primitives::Rectangle),
+}
+
+/// Which lines are drawn on the court. They are only decoration, nothing bounces off them.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CourtMarkings {
+    /// Dashed line down the middle, not in four-player games.
+    pub center_line: bool,
+    /// Lines along the top and bottom walls, not in four-player games where they are goals.
+    pub borders: bool,
+    /// Lines just inside the wall paddles, the back ones in doubles. The ball scores once it is
+    /// past them.
+    pub goal_zones: bool,
+}
+
+impl Default for CourtMarkings {
+    fn default() -> Self {
+        CourtMarkings {
+            center_line: true,
+            borders: false,
+            goal_zones: false,
+        }
+    }
+}
+
+/// A line of text. `position` is the vertical middle of the text, horizontally aligned according
+/// to `alignment`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ScreenText {
+    pub position: Point,
+    pub content: String<TEXT_CAPACITY>,
+    pub alignment: Alignment,
+}
+
+impl ScreenText {
+    /// Text longer than `TEXT_CAPACITY` is cut off.
+    pub fn new(position: Point, text: &str, alignment: Alignment) -> ScreenText {
+        let mut content = String::new();
+        for character in text.chars() {
+            if content.push(character).is_err() {
+                break;
+            }
+        }
+        ScreenText {
+            position,
+            content,
+            alignment,
+        }
+    }
+}
+
+#[derive(Debug)]
+enum DrawableGameOject<'a> {
+    Paddle(&'a mut Paddle),
+    Ball(&'a mut Ball),
+}
+
+impl DrawableGameOject<'_> {
+    fn set_moved_status(&mut self, new_status: bool) {
+        match self {
+            DrawableGameOject::Ball(ref mut ball) => ball.has_moved = new_status,
+            DrawableGameOject::Paddle(ref mut paddle) => paddle.has_moved = new_status,
+        };
+    }
+    fn get_moved_status(&self) -> bool {
+        match &self {
+            DrawableGameOject::Ball(ball) => ball.has_moved,
+            DrawableGameOject::Paddle(paddle) => paddle.has_moved,
+        }
+    }
+    fn as_shapes(&self) -> Vec<ScreenObject, 2> {
+        match self {
+            DrawableGameOject::Ball(ball) => ball.as_shapes(),
+            DrawableGameOject::Paddle(paddle) => paddle.as_shapes(),
+        }
+    }
+}
+
+/// The game itself, `R` makes the serve angles unpredictable.
+pub struct Game<R = XorShift32> {
+    /// The paddles of each side that has any, left and right first. The front paddle comes first,
+    /// doubles games have a back one behind it.
+    paddles: Vec<(LeftRightPosition, Vec<Paddle, PADDLES_PER_SIDE>), 4>,
+    x_pixels: u32,
+    y_pixels: u32,
+    ball: Ball,
+    time_tick: TimeTick,
+    score: Score,
+    points_to_win: u32,
+    paddle_bounce: PaddleBounce,
+    speed_up: SpeedUp,
+    serve_velocity: Velocity,
+    rally_hits: u32,
+    /// Who hit the ball since it was served.
+    last_hit: Option<LeftRightPosition>,
+    court_markings: CourtMarkings,
+    serve_direction: ServeDirection,
+    next_serve: LeftRightPosition,
+    serve_delay: u32,
+    ticks_until_serve: u32,
+    random: R,
+}
+
+impl Game {
+    pub fn builder() -> GameBuilder {
+        GameBuilder::default()
+    }
+}
+
+impl<R: Random> Game<R> {
+    fn on_screen_objects(&mut self) -> Vec<DrawableGameOject<'_>, 9> {
+        let mut objects: Vec<DrawableGameOject, 9> = Vec::new();
+        objects
+            .push(DrawableGameOject::Ball(&mut self.ball))
+            .unwrap();
+        for (_, paddles) in self.paddles.iter_mut() {
+            for paddle in paddles.iter_mut() {
+                objects.push(DrawableGameOject::Paddle(paddle)).unwrap();
+            }
+        }
+        objects
+    }
+    pub fn get_content_to_display(&mut self) -> Vec<ScreenObject, CONTENT_CAPACITY> {
+        let mut all_shapes: Vec<ScreenObject, CONTENT_CAPACITY> = Vec::new();
+        // The court and the score go first so that the paddles and ball are drawn on top of them.
+        all_shapes.extend(self.get_court_content());
+        all_shapes.extend(self.get_score_content());
+        // TODO: improve with less copying. from slices?
+        for (_, paddles) in self.paddles.iter() {
+            for paddle in paddles.iter() {
+                all_shapes.extend(paddle.as_shapes().iter().cloned());
+            }
+        }
+        all_shapes.extend(self.ball.as_shapes().iter().cloned());
+
+        all_shapes
+    }
+    /// The court markings, they never move. `Graphics::redraw` repairs them when something that
+    /// moved over them is erased.
+    pub fn get_court_content(&self) -> Vec<ScreenObject, COURT_CAPACITY> {
+        let mut court: Vec<ScreenObject, COURT_CAPACITY> = Vec::new();
+        let (width, height) = (self.x_pixels as i32, self.y_pixels as i32);
+        let mut add_line = |x: i32, y: i32, line_width: u32, line_height: u32| {
+            let line = Rectangle::new(Point { x, y }, Size::new(line_width, line_height));
+            court.push(ScreenObject::CourtMarking(line)).unwrap();
+        };
+        let four_players = self.has_four_players();
+        if self.court_markings.center_line && !four_players {
+            let period = height / CENTER_LINE_DASHES as i32;
+            let x = (width - CENTER_LINE_WIDTH as i32) / 2;
+            for dash in 0..CENTER_LINE_DASHES as i32 {
+                // Half dash, half gap, with the dash in the middle of its period.
+                let y = dash * period + period / 4;
+                add_line(x, y, CENTER_LINE_WIDTH, (period / 2).max(1) as u32);
+            }
+        }
+        if self.court_markings.borders && !four_players {
+            add_line(0, 0, self.x_pixels, 1);
+            add_line(0, height - 1, self.x_pixels, 1);
+        }
+        if self.court_markings.goal_zones {
+            // In front of the paddles at the walls.
+            for (side, paddles) in self.paddles.iter() {
+                let back = paddles[paddles.len() - 1];
+                let (x, y) = (back.top_left_pos.x.round(), back.top_left_pos.y.round());
+                match side {
+                    LeftRightPosition::Left => {
+                        add_line(x + back.x_size as i32, 0, 1, self.y_pixels)
+                    }
+                    LeftRightPosition::Right => add_line(x - 1, 0, 1, self.y_pixels),
+                    LeftRightPosition::Top => add_line(0, y + back.y_size as i32, self.x_pixels, 1),
+                    LeftRightPosition::Bottom => add_line(0, y - 1, self.x_pixels, 1),
+                }
+            }
+        }
+        court
+    }
+    pub fn get_score_content(&self) -> Vec<ScreenObject, 4> {
+        let quarter_width = (self.x_pixels / 4) as i32;
+        let quarter_height = (self.y_pixels / 4) as i32;
+        let at = |x, y| Point { x, y };
+        let mut scores: Vec<(u32, Point), 4> = Vec::new();
+        match self.has_four_players() {
+            false => scores.extend([
+                (self.score.left, at(quarter_width, SCORE_TEXT_HEIGHT)),
+                (self.score.right, at(3 * quarter_width, SCORE_TEXT_HEIGHT)),
+            ]),
+            // The top wall is a goal, so each score goes in front of its player's wall.
+            true => scores.extend([
+                (self.score.left, at(quarter_width, 2 * quarter_height)),
+                (self.score.right, at(3 * quarter_width, 2 * quarter_height)),
+                (self.score.top, at(2 * quarter_width, quarter_height)),
+                (self.score.bottom, at(2 * quarter_width, 3 * quarter_height)),
+            ]),
+        }
+        let mut score_text: Vec<ScreenObject, 4> = Vec::new();
+        for (points, position) in scores {
+            let mut text: String<TEXT_CAPACITY> = String::new();
+            write!(text, "{}", points).unwrap();
+            score_text
+                .push(ScreenObject::Text(ScreenText::new(
+                    position,
+                    &text,
+                    Alignment::Center,
+                )))
+                .unwrap();
+        }
+        score_text
+    }
+    /// A message centered on the screen, e.g. "LEFT WINS".
+    pub fn get_message_content(&self, message: &str) -> ScreenObject {
+        let center = self.get_screen_dimensions().center();
+        ScreenObject::Text(ScreenText::new(center, message, Alignment::Center))
+    }
+    pub fn reset_position_update_indicators(&mut self) {
+        for object in self.on_screen_objects().iter_mut() {
+            object.set_moved_status(false);
+        }
+    }
+    pub fn get_moved_content(&mut self) -> Vec<ScreenObject, CONTENT_CAPACITY> {
+        let mut moved_shapes: Vec<ScreenObject, CONTENT_CAPACITY> = Vec::new();
+        for moved_object in self
+            .on_screen_objects()
+            .iter_mut()
+            .filter(|object| object.get_moved_status())
+        {
+            moved_shapes.extend(moved_object.as_shapes());
+        }
+        moved_shapes
+    }
+    pub fn let_ball_move(&mut self) -> GameState {
+        if self.ticks_until_serve > 0 {
+            self.ticks_until_serve = self
+                .ticks_until_serve
+                .saturating_sub(self.time_tick.time_step);
+            return GameState::Ongoing;
+        }
+        let screen = self.get_screen_dimensions();
+        let obstacles = Obstacles {
+            // The top and bottom walls are goals.
+            walls: match self.has_four_players() {
+                true => None,
+                false => Some(screen),
+            },
+            paddles: &self.paddles,
+        };
+        let ticks = Fixed::from_int(self.time_tick.time_step as i32);
+        let hit_paddles = self.ball.move_for(ticks, &obstacles, &self.paddle_bounce);
+        self.ball.has_moved = true;
+        for side in hit_paddles {
+            self.hit_by(side);
+        }
+        match self.get_loser(&screen) {
+            Some(loser) => self.award_point(loser),
+            None => GameState::Ongoing,
+        }
+    }
+    pub fn get_score(&self) -> Score {
+        self.score
+    }
+    pub fn get_ball(&self) -> Ball {
+        self.ball
+    }
+    /// The front paddle, `None` for the top and bottom walls of a two-player game.
+    pub fn get_paddle(&self, side: &LeftRightPosition) -> Option<Paddle> {
+        self.get_paddles(side).first().copied()
+    }
+    /// All paddles on `side` in the order of `PaddleRow::ALL`, none for the top and bottom walls
+    /// of a two-player game.
+    pub fn get_paddles(&self, side: &LeftRightPosition) -> &[Paddle] {
+        paddles_on(&self.paddles, side)
+    }
+    /// The sides with a paddle, left and right first.
+    pub fn get_sides(&self) -> Vec<LeftRightPosition, 4> {
+        self.paddles.iter().map(|(side, _)| *side).collect()
+    }
+    /// Whether the top and bottom walls are goals with paddles of their own.
+    pub fn has_four_players(&self) -> bool {
+        self.paddles.len() == 4
+    }
+    /// The ball waits in the middle of the court for this many more ticks before it is served.
+    pub fn get_ticks_until_serve(&self) -> u32 {
+        self.ticks_until_serve
+    }
+    /// Serves right away instead of waiting for the serve delay to run out.
+    pub fn serve_now(&mut self) {
+        self.ticks_until_serve = 0;
+    }
+    /// Number of ticks the next `let_ball_move` and `move_paddle` calls cover.
+    pub fn set_time_step(&mut self, time_step: u32) {
+        self.time_tick.time_step = time_step;
+    }
+    /// Moves the paddle in `row` on `side`, if there is one.
+    pub fn move_paddle(
+        &mut self,
+        side: &LeftRightPosition,
+        row: &PaddleRow,
+        direction: InpuDirection,
+    ) {
+        self.move_paddle_at_speed(side, row, direction, Fixed::ONE);
+    }
+    /// Like `move_paddle`, at `speed` from 0 to 1 times the full paddle speed.
+    pub fn move_paddle_at_speed(
+        &mut self,
+        side: &LeftRightPosition,
+        row: &PaddleRow,
+        direction: InpuDirection,
+        speed: Fixed,
+    ) {
+        let full_step =
+            Fixed::from_int((self.time_tick.max_paddle_movement * self.time_tick.time_step) as i32);
+        let step_size = full_step * speed.max(Fixed::ZERO).min(Fixed::ONE);
+        match direction {
+            InpuDirection::Up => self.move_paddle_along_wall(side, row, -step_size),
+            InpuDirection::Down => self.move_paddle_along_wall(side, row, step_size),
+            InpuDirection::Stay => self.move_paddle_along_wall(side, row, Fixed::ZERO),
+        };
+    }
+    /// Puts the paddle `position_percentage` of the way from the top of the court to the bottom,
+    /// or from left to right for the top and bottom paddles, for controls like the knobs of the
+    /// original Pong.
+    pub fn move_paddle_to(
+        &mut self,
+        side: &LeftRightPosition,
+        row: &PaddleRow,
+        position_percentage: u8,
+    ) {
+        let paddle = match self.get_paddles(side).get(row_index(row)) {
+            Some(paddle) => *paddle,
+            None => return,
+        };
+        let area = self.get_paddle_area(side);
+        // Seen mirrored along the diagonal, the top and bottom paddles move up and down too.
+        let (paddle, area) = match side {
+            LeftRightPosition::Top | LeftRightPosition::Bottom => {
+                (paddle.transposed(), transposed_area(&area))
+            }
+            LeftRightPosition::Left | LeftRightPosition::Right => (paddle, area),
+        };
+        let travel = area.size.height as i32 - paddle.y_size as i32;
+        let target = Fixed::from_int(area.top_left.y)
+            + Fixed::from_ratio(travel * position_percentage.min(100) as i32, 100);
+        self.move_paddle_along_wall(side, row, target - paddle.top_left_pos.y);
+    }
+    /// Puts the ball in the middle of the court and serves it once the serve delay is over.
+    pub fn start_new_game(&mut self) {
+        self.ball = self.ball.set_position(self.get_default_ball_position());
+        self.ball.has_moved = true;
+        let speed = self.serve_velocity.vx.abs();
+        let angle = self.random_serve_angle();
+        self.ball.velocity = match self.next_serve {
+            LeftRightPosition::Left => Velocity {
+                vx: -speed,
+                vy: angle,
+            },
+            LeftRightPosition::Right => Velocity {
+                vx: speed,
+                vy: angle,
+            },
+            LeftRightPosition::Top => Velocity {
+                vx: angle,
+                vy: -speed,
+            },
+            LeftRightPosition::Bottom => Velocity {
+                vx: angle,
+                vy: speed,
+            },
+        };
+        self.ticks_until_serve = self.serve_delay;
+        self.rally_hits = 0;
+        self.last_hit = None;
+    }
+    pub fn start_new_match(&mut self) {
+        self.score = Score::default();
+        self.start_new_game();
+    }
+
+    /// Moves down or, for the top and bottom paddles, right by `step`.
+    fn move_paddle_along_wall(&mut self, side: &LeftRightPosition, row: &PaddleRow, step: Fixed) {
+        let area = self.get_paddle_area(side);
+        let paddle = match self
+            .paddles
+            .iter_mut()
+            .find(|(paddle_side, _)| paddle_side == side)
+            .and_then(|(_, paddles)| paddles.get_mut(row_index(row)))
+        {
+            Some(paddle) => paddle,
+            None => return,
+        };
+        paddle.y_movement = Fixed::ZERO;
+        if step != Fixed::ZERO {
+            let movement = match side {
+                LeftRightPosition::Top | LeftRightPosition::Bottom => FixedPoint {
+                    x: step,
+                    y: Fixed::ZERO,
+                },
+                LeftRightPosition::Left | LeftRightPosition::Right => FixedPoint {
+                    x: Fixed::ZERO,
+                    y: step,
+                },
+            };
+            let moved_paddle = Paddle {
+                top_left_pos: paddle.top_left_pos + movement,
+                x_size: paddle.x_size,
+                y_size: paddle.y_size,
+                has_moved: true,
+                y_movement: step,
+                side: paddle.side,
+            };
+            if moved_paddle.is_within(&area) {
+                *paddle = moved_paddle;
+            }
+        }
+    }
+    /// Where the paddle on `side` may go. In four-player games nobody may go into the corners,
+    /// so that the paddles don't run into each other.
+    fn get_paddle_area(&self, side: &LeftRightPosition) -> Rectangle {
+        let screen = self.get_screen_dimensions();
+        let corner = match self.has_four_players() {
+            true => self.get_paddles(&LeftRightPosition::Left)[0].x_size,
+            false => return screen,
+        };
+        match side {
+            LeftRightPosition::Left | LeftRightPosition::Right => Rectangle::new(
+                Point {
+                    x: 0,
+                    y: corner as i32,
+                },
+                Size::new(self.x_pixels, self.y_pixels.saturating_sub(2 * corner)),
+            ),
+            LeftRightPosition::Top | LeftRightPosition::Bottom => Rectangle::new(
+                Point {
+                    x: corner as i32,
+                    y: 0,
+                },
+                Size::new(self.x_pixels.saturating_sub(2 * corner), self.y_pixels),
+            ),
+        }
+    }
+    pub fn get_screen_dimensions(&self) -> Rectangle {
+        Rectangle {
+            top_left: Point { x: 0, y: 0 },
+            size: Size {
+                width: self.x_pixels,
+                height: self.y_pixels,
+            },
+        }
+    }
+    fn get_default_ball_position(&self) -> Point {
+        self.get_screen_dimensions().center()
+    }
+    /// Anything from straight across up to the serve velocity's vertical speed, up or down.
+    fn random_serve_angle(&mut self) -> Fixed {
+        let max_vy = self.serve_velocity.vy.abs();
+        let percent = self.random.next_between(100) as i32;
+        max_vy * Fixed::from_ratio(percent, 100)
+    }
+    fn hit_by(&mut self, side: LeftRightPosition) {
+        self.rally_hits += 1;
+        self.last_hit = Some(side);
+        self.speed_up_ball(&side);
+    }
+    /// Speeds the ball up away from the paddle on `side`.
+    fn speed_up_ball(&mut self, side: &LeftRightPosition) {
+        let serve_speed = self.serve_velocity.vx.abs();
+        let max_speed = Fixed::from_int(self.time_tick.max_ball_movement as i32).max(serve_speed);
+        let increases = self.speed_up.increases_after(self.rally_hits) as i32;
+        let speed = serve_speed + Fixed::from_int(increases);
+        let velocity = &mut self.ball.velocity;
+        let (hit_axis, cross_axis) = match side {
+            LeftRightPosition::Left | LeftRightPosition::Right => {
+                (&mut velocity.vx, &mut velocity.vy)
+            }
+            LeftRightPosition::Top | LeftRightPosition::Bottom => {
+                (&mut velocity.vy, &mut velocity.vx)
+            }
+        };
+        *hit_axis = speed.min(max_speed) * direction_of(*hit_axis);
+        // The paddle's English is capped as well, so neither axis outruns the sweep.
+        *cross_axis = (*cross_axis).clamp(-max_speed, max_speed);
+    }
+    fn award_point(&mut self, loser: LeftRightPosition) -> GameState {
+        // Whoever hit the ball last scores. If nobody did, or the loser did, it's the player
+        // across the court, as in a two-player game.
+        let winner = match self.last_hit {
+            Some(hitter) if hitter != loser => hitter,
+            _ => loser.opposite(),
+        };
+        let points = match winner {
+            LeftRightPosition::Left => &mut self.score.left,
+            LeftRightPosition::Right => &mut self.score.right,
+            LeftRightPosition::Top => &mut self.score.top,
+            LeftRightPosition::Bottom => &mut self.score.bottom,
+        };
+        *points += 1;
+        let points = *points;
+        self.next_serve = match self.serve_direction {
+            ServeDirection::Alternate => {
+                let sides = self.get_sides();
+                let served = sides.iter().position(|side| *side == self.next_serve);
+                sides[served.map_or(0, |served| (served + 1) % sides.len())]
+            }
+            ServeDirection::TowardsLoser => loser,
+        };
+        let point_winner = GameOver::won_by(winner);
+        match points >= self.points_to_win {
+            true => GameState::Finnished(point_winner),
+            false => GameState::PointScored(point_winner),
+        }
+    }
+    fn get_loser(&self, screen: &Rectangle) -> Option<LeftRightPosition> {
+        if self.ball.left_player_has_lost_ball(screen) {
+            return Some(LeftRightPosition::Left);
+        } else if self.ball.right_player_has_lost_ball(screen) {
+            return Some(LeftRightPosition::Right);
+        } else if self.has_four_players() && self.ball.top_player_has_lost_ball(screen) {
+            return Some(LeftRightPosition::Top);
+        } else if self.has_four_players() && self.ball.bottom_player_has_lost_ball(screen) {
+            return Some(LeftRightPosition::Bottom);
+        }
+        None
+    }
+}
+
+/// Mirrored along the diagonal, x and y swap places.
+pub fn transposed_area(area: &Rectangle) -> Rectangle {
+    Rectangle {
+        top_left: Point {
+            x: area.top_left.y,
+            y: area.top_left.x,
+        },
+        size: Size {
+            width: area.size.height,
+            height: area.size.width,
+        },
+    }
+}
+
+/// Apart from `Game::get_paddles`, so that the ball can bounce off them while the paddles are
+/// borrowed.
+fn paddles_on<'a>(
+    paddles: &'a [(LeftRightPosition, Vec<Paddle, PADDLES_PER_SIDE>)],
+    side: &LeftRightPosition,
+) -> &'a [Paddle] {
+    match paddles.iter().find(|(paddle_side, _)| paddle_side == side) {
+        Some((_, paddles)) => paddles,
+        None => &[],
+    }
+}
+
+fn row_index(row: &PaddleRow) -> usize {
+    match row {
+        PaddleRow::Front => 0,
+        PaddleRow::Back => 1,
+    }
+}
+
+fn direction_of(speed: Fixed) -> i32 {
+    match speed < Fixed::ZERO {
+        true => -1,
+        false => 1,
+    }
+}
+
+#[derive(Clone, Copy)]
+pub struct GameBuilder<R = XorShift32> {
+    left_paddle: Paddle,
+    right_paddle: Paddle,
+    ball: Ball,
+    x_pixels: u32,
+    y_pixels: u32,
+    time_tick: TimeTick,
+    points_to_win: u32,
+    paddle_bounce: PaddleBounce,
+    speed_up: SpeedUp,
+    court_markings: CourtMarkings,
+    serve_direction: ServeDirection,
+    serve_delay: u32,
+    four_players: bool,
+    doubles: bool,
+    random: R,
+}
+
+impl Default for GameBuilder {
+    fn default() -> Self {
+        GameBuilder {
+            left_paddle: Paddle::default(),
+            right_paddle: Paddle::default(),
+            ball: Ball::default(),
+            x_pixels: 0,
+            y_pixels: 0,
+            time_tick: TimeTick::default(),
+            points_to_win: DEFAULT_POINTS_TO_WIN,
+            paddle_bounce: PaddleBounce::default(),
+            speed_up: SpeedUp::default(),
+            court_markings: CourtMarkings::default(),
+            serve_direction: ServeDirection::default(),
+            serve_delay: 0,
+            four_players: false,
+            doubles: false,
+            random: XorShift32::default(),
+        }
+    }
+}
+
+impl GameBuilder {
+    pub fn new(x_size: u32, y_size: u32) -> GameBuilder {
+        GameBuilder {
+            x_pixels: x_size,
+            y_pixels: y_size,
+            ..GameBuilder::default()
+        }
+    }
+}
+
+impl<R: Random + Copy> GameBuilder<R> {
+    pub fn ball_radius(&self, radius: u32) -> GameBuilder<R> {
+        GameBuilder {
+            ball: Ball {
+                position: self.ball.position,
+                radius,
+                velocity: self.ball.velocity,
+                has_moved: true,
+            },
+            ..*self
+        }
+    }
+    pub fn paddle_size(&self, size: Size) -> GameBuilder<R> {
+        GameBuilder {
+            left_paddle: Paddle {
+                top_left_pos: FixedPoint::default(),
+                x_size: size.width,
+                y_size: size.height,
+                has_moved: true,
+                y_movement: Fixed::ZERO,
+                side: LeftRightPosition::Left,
+            },
+            right_paddle: Paddle {
+                top_left_pos: FixedPoint::from(Point {
+                    x: (self.x_pixels - size.width) as i32,
+                    y: 0,
+                }),
+                x_size: size.width,
+                y_size: size.height,
+                has_moved: true,
+                y_movement: Fixed::ZERO,
+                side: LeftRightPosition::Right,
+            },
+            ..*self
+        }
+    }
+    pub fn time_tick(&self, time_tick: TimeTick) -> GameBuilder<R> {
+        GameBuilder { time_tick, ..*self }
+    }
+    pub fn initial_ball_velocity(&self, velocity: Velocity) -> GameBuilder<R> {
+        GameBuilder {
+            ball: Ball {
+                position: self.ball.position,
+                radius: self.ball.radius,
+                velocity,
+                has_moved: self.ball.has_moved,
+            },
+            ..*self
+        }
+    }
+    /// Number of points a side needs to win the match.
+    pub fn points_to_win(&self, points_to_win: u32) -> GameBuilder<R> {
+        GameBuilder {
+            points_to_win,
+            ..*self
+        }
+    }
+    pub fn paddle_bounce(&self, paddle_bounce: PaddleBounce) -> GameBuilder<R> {
+        GameBuilder {
+            paddle_bounce,
+            ..*self
+        }
+    }
+    /// How the ball speeds up with each paddle hit during a rally.
+    pub fn ball_speed_up(&self, speed_up: SpeedUp) -> GameBuilder<R> {
+        GameBuilder { speed_up, ..*self }
+    }
+    /// Lines drawn on the court, by default only the centre line.
+    pub fn court_markings(&self, court_markings: CourtMarkings) -> GameBuilder<R> {
+        GameBuilder {
+            court_markings,
+            ..*self
+        }
+    }
+
+    pub fn serve_direction(&self, serve_direction: ServeDirection) -> GameBuilder<R> {
+        GameBuilder {
+            serve_direction,
+            ..*self
+        }
+    }
+    /// Ticks the ball waits in the middle of the court before each serve.
+    pub fn serve_delay(&self, serve_delay: u32) -> GameBuilder<R> {
+        GameBuilder {
+            serve_delay,
+            ..*self
+        }
+    }
+    /// Adds paddles on the top and bottom walls, which turn into goals. They are as long and as
+    /// thick as the left and right paddles and move sideways, nobody can go into the corners.
+    pub fn four_players(&self, four_players: bool) -> GameBuilder<R> {
+        GameBuilder {
+            four_players,
+            ..*self
+        }
+    }
+    /// Gives every side a second paddle with its own player. The back paddle stays at the wall
+    /// and the front one stands a quarter of the way across the court.
+    pub fn doubles(&self, doubles: bool) -> GameBuilder<R> {
+        GameBuilder { doubles, ..*self }
+    }
+
+    /// Where the randomness comes from, e.g. `XorShift32::new(seed)` with a seed that differs
+    /// each time the game is switched on, or a fixed one for games that play out the same.
+    pub fn random<S: Random + Copy>(&self, random: S) -> GameBuilder<S> {
+        GameBuilder {
+            left_paddle: self.left_paddle,
+            right_paddle: self.right_paddle,
+            ball: self.ball,
+            x_pixels: self.x_pixels,
+            y_pixels: self.y_pixels,
+            time_tick: self.time_tick,
+            points_to_win: self.points_to_win,
+            paddle_bounce: self.paddle_bounce,
+            speed_up: self.speed_up,
+            court_markings: self.court_markings,
+            serve_direction: self.serve_direction,
+            serve_delay: self.serve_delay,
+            four_players: self.four_players,
+            doubles: self.doubles,
+            random,
+        }
+    }
+
+    pub fn build(self) -> Game<R> {
+        let corner = match self.four_players {
+            true => self.left_paddle.x_size as i32,
+            false => 0,
+        };
+        let below_corner = |paddle: Paddle| Paddle {
+            top_left_pos: paddle.top_left_pos + FixedPoint::from(Point { x: 0, y: corner }),
+            ..paddle
+        };
+        let across = |y: i32| Paddle {
+            top_left_pos: FixedPoint::from(Point { x: corner, y }),
+            ..self.left_paddle.transposed()
+        };
+        let mut at_walls: Vec<(LeftRightPosition, Paddle), 4> = Vec::new();
+        at_walls.extend([
+            (LeftRightPosition::Left, below_corner(self.left_paddle)),
+            (LeftRightPosition::Right, below_corner(self.right_paddle)),
+        ]);
+        if self.four_players {
+            at_walls.extend([
+                (LeftRightPosition::Top, across(0)),
+                (
+                    LeftRightPosition::Bottom,
+                    across(self.y_pixels as i32 - corner),
+                ),
+            ]);
+        }
+        let (quarter_width, quarter_height) =
+            ((self.x_pixels / 4) as i32, (self.y_pixels / 4) as i32);
+        let in_front = |side: &LeftRightPosition, paddle: Paddle| {
+            let step = match side {
+                LeftRightPosition::Left => Point::new(quarter_width, 0),
+                LeftRightPosition::Right => Point::new(-quarter_width, 0),
+                LeftRightPosition::Top => Point::new(0, quarter_height),
+                LeftRightPosition::Bottom => Point::new(0, -quarter_height),
+            };
+            Paddle {
+                top_left_pos: paddle.top_left_pos + FixedPoint::from(step),
+                ..paddle
+            }
+        };
+        let paddles = at_walls
+            .into_iter()
+            .map(|(side, paddle)| {
+                let paddle = Paddle { side, ..paddle };
+                let mut paddles: Vec<Paddle, PADDLES_PER_SIDE> = Vec::new();
+                match self.doubles {
+                    true => paddles.extend([in_front(&side, paddle), paddle]),
+                    false => paddles.extend([paddle]),
+                }
+                (side, paddles)
+            })
+            .collect();
+        Game {
+            paddles,
+            x_pixels: self.x_pixels,
+            y_pixels: self.y_pixels,
+            ball: self.ball,
+            time_tick: self.time_tick,
+            score: Score::default(),
+            points_to_win: self.points_to_win,
+            paddle_bounce: self.paddle_bounce,
+            speed_up: self.speed_up,
+            serve_velocity: self.ball.velocity,
+            rally_hits: 0,
+            last_hit: None,
+            court_markings: self.court_markings,
+            serve_direction: self.serve_direction,
+            // The first serve goes the way the initial velocity points.
+            next_serve: match self.ball.velocity.vx < Fixed::ZERO {
+                true => LeftRightPosition::Left,
+                false => LeftRightPosition::Right,
+            },
+            serve_delay: self.serve_delay,
+            ticks_until_serve: 0,
+            random: self.random,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn game() -> Game {
+        GameBuilder::new(160, 128)
+            .ball_radius(3)
+            .paddle_size(Size {
+                width: 6,
+                height: 40,
+            })
+            .time_tick(TimeTick {
+                max_ball_movement: 5,
+                max_paddle_movement: 5,
+                time_step: 1,
+            })
+            .initial_ball_velocity(Velocity::from_pixels(1, 1))
+            .build()
+    }
+
+    fn front_paddle(game: &Game, side: LeftRightPosition) -> Paddle {
+        game.get_paddle(&side).unwrap()
+    }
+
+    #[test]
+    fn builder_places_paddles_at_the_sides() {
+        let game = game();
+        assert_eq!(
+            front_paddle(&game, LeftRightPosition::Left)
+                .top_left_pos
+                .round(),
+            Point { x: 0, y: 0 }
+        );
+        assert_eq!(
+            front_paddle(&game, LeftRightPosition::Right)
+                .top_left_pos
+                .round(),
+            Point { x: 154, y: 0 }
+        );
+    }
+
+    #[test]
+    fn ball_moves_according_to_velocity() {
+        let mut game = game();
+        game.start_new_game();
+        game.ball.velocity = Velocity::from_pixels(1, 1);
+        game.reset_position_update_indicators();
+
+        assert_eq!(game.let_ball_move(), GameState::Ongoing);
+        assert_eq!(game.ball.position.round(), Point { x: 80, y: 64 });
+        assert!(game.ball.has_moved);
+    }
+
+    #[test]
+    fn ball_movement_scales_with_time_step() {
+        let mut game = game();
+        game.set_time_step(3);
+        game.start_new_game();
+        game.ball.velocity = Velocity::from_pixels(1, 1);
+
+        game.let_ball_move();
+        assert_eq!(game.ball.position.round(), Point { x: 82, y: 66 });
+    }
+
+    #[test]
+    fn ball_is_served_from_the_middle() {
+        let mut game = game();
+        game.ball.position = FixedPoint::from(Point { x: 20, y: 100 });
+        game.start_new_game();
+        assert_eq!(game.ball.position.round(), Point { x: 79, y: 63 });
+        assert_eq!(game.ball.velocity.vx, Fixed::from_int(1));
+    }
+
+    #[test]
+    fn serve_angle_varies_within_serve_velocity() {
+        let mut game = game();
+        let mut angles: Vec<Fixed, 20> = Vec::new();
+        for _ in 0..20 {
+            game.start_new_game();
+            assert!(game.ball.velocity.vy.abs() <= Fixed::ONE);
+            angles.push(game.ball.velocity.vy).unwrap();
+        }
+        assert!(angles.iter().any(|vy| *vy > Fixed::ZERO));
+        assert!(angles.iter().any(|vy| *vy < Fixed::ZERO));
+    }
+
+    /// Always gives the same number.
+    #[derive(Clone, Copy)]
+    struct Constant(u32);
+
+    impl Random for Constant {
+        fn next_u32(&mut self) -> u32 {
+            self.0
+        }
+    }
+
+    #[test]
+    fn serve_angle_comes_from_random_source() {
+        let serve_with = |random| {
+            let mut game = GameBuilder::new(160, 128)
+                .initial_ball_velocity(Velocity::from_pixels(1, 2))
+                .random(random)
+                .build();
+            game.start_new_game();
+            game.ball.velocity.vy
+        };
+        assert_eq!(serve_with(Constant(0)), Fixed::from_int(-2));
+        assert_eq!(serve_with(Constant(100)), Fixed::ZERO);
+        assert_eq!(serve_with(Constant(200)), Fixed::from_int(2));
+    }
+
+    #[test]
+    fn same_seed_gives_same_serves() {
+        let serves = || {
+            let mut game = GameBuilder::new(160, 128)
+                .initial_ball_velocity(Velocity::from_pixels(1, 1))
+                .random(XorShift32::new(42))
+                .build();
+            let mut angles = [Fixed::ZERO; 5];
+            for angle in angles.iter_mut() {
+                game.start_new_game();
+                *angle = game.ball.velocity.vy;
+            }
+            angles
+        };
+        assert_eq!(serves(), serves());
+    }
+
+    #[test]
+    fn ball_is_served_towards_loser_by_default() {
+        let mut game = game();
+        miss_on_right_side(&mut game);
+        game.start_new_game();
+        assert!(game.ball.velocity.vx > Fixed::ZERO);
+        miss_on_right_side(&mut game);
+        game.start_new_game();
+        assert!(game.ball.velocity.vx > Fixed::ZERO);
+    }
+
+    #[test]
+    fn serve_direction_can_alternate() {
+        let mut game = GameBuilder::new(160, 128)
+            .paddle_size(Size {
+                width: 6,
+                height: 40,
+            })
+            .time_tick(TimeTick {
+                max_ball_movement: 5,
+                max_paddle_movement: 5,
+                time_step: 1,
+            })
+            .initial_ball_velocity(Velocity::from_pixels(-1, 1))
+            .serve_direction(ServeDirection::Alternate)
+            .build();
+        game.start_new_game();
+        assert!(game.ball.velocity.vx < Fixed::ZERO);
+        let mut serves = [0; 3];
+        for serve in serves.iter_mut() {
+            miss_on_right_side(&mut game);
+            game.start_new_game();
+            *serve = game.ball.velocity.vx.signum();
+        }
+        assert_eq!(serves, [1, -1, 1]);
+    }
+
+    #[test]
+    fn ball_waits_before_serve() {
+        let mut game = GameBuilder::new(160, 128)
+            .ball_radius(3)
+            .time_tick(TimeTick {
+                max_ball_movement: 5,
+                max_paddle_movement: 5,
+                time_step: 1,
+            })
+            .initial_ball_velocity(Velocity::from_pixels(1, 1))
+            .serve_delay(3)
+            .build();
+        game.start_new_game();
+        game.set_time_step(2);
+        game.let_ball_move();
+        assert_eq!(game.get_ticks_until_serve(), 1);
+        game.let_ball_move();
+        assert_eq!(game.get_ticks_until_serve(), 0);
+        assert_eq!(game.ball.position.round(), Point { x: 79, y: 63 });
+
+        game.let_ball_move();
+        assert_ne!(game.ball.position.round(), Point { x: 79, y: 63 });
+    }
+
+    #[test]
+    fn right_wins_when_left_misses() {
+        let mut game = game();
+        game.ball.position = FixedPoint::from(Point { x: 0, y: 100 });
+        game.ball.velocity = Velocity::from_pixels(-1, 1);
+
+        assert_eq!(
+            game.let_ball_move(),
+            GameState::PointScored(GameOver::RightWins)
+        );
+        assert_eq!(
+            game.get_score(),
+            Score {
+                left: 0,
+                right: 1,
+                ..Score::default()
+            }
+        );
+    }
+
+    #[test]
+    fn left_wins_when_right_misses() {
+        let mut game = game();
+        game.ball.position = FixedPoint::from(Point { x: 160, y: 100 });
+        game.ball.velocity = Velocity::from_pixels(1, 1);
+
+        assert_eq!(
+            game.let_ball_move(),
+            GameState::PointScored(GameOver::LeftWins)
+        );
+        assert_eq!(
+            game.get_score(),
+            Score {
+                left: 1,
+                right: 0,
+                ..Score::default()
+            }
+        );
+    }
+
+    fn miss_on_right_side(game: &mut Game) -> GameState {
+        game.ball.position = FixedPoint::from(Point { x: 160, y: 100 });
+        game.ball.velocity = Velocity::from_pixels(1, 1);
+        game.let_ball_move()
+    }
+
+    #[test]
+    fn match_is_won_at_points_to_win() {
+        let mut game = GameBuilder::new(160, 128)
+            .paddle_size(Size {
+                width: 6,
+                height: 40,
+            })
+            .time_tick(TimeTick {
+                max_ball_movement: 5,
+                max_paddle_movement: 5,
+                time_step: 1,
+            })
+            .points_to_win(3)
+            .build();
+
+        for _ in 0..2 {
+            assert_eq!(
+                miss_on_right_side(&mut game),
+                GameState::PointScored(GameOver::LeftWins)
+            );
+            game.start_new_game();
+        }
+        assert_eq!(
+            miss_on_right_side(&mut game),
+            GameState::Finnished(GameOver::LeftWins)
+        );
+        assert_eq!(
+            game.get_score(),
+            Score {
+                left: 3,
+                right: 0,
+                ..Score::default()
+            }
+        );
+    }
+
+    #[test]
+    fn new_game_keeps_score_and_new_match_resets_it() {
+        let mut game = game();
+        miss_on_right_side(&mut game);
+
+        game.start_new_game();
+        assert_eq!(
+            game.get_score(),
+            Score {
+                left: 1,
+                right: 0,
+                ..Score::default()
+            }
+        );
+        game.start_new_match();
+        assert_eq!(game.get_score(), Score::default());
+    }
+
+    #[test]
+    fn ball_moves_less_than_a_pixel_per_step() {
+        let mut game = game();
+        game.ball.position = FixedPoint::from(Point { x: 50, y: 50 });
+        game.ball.velocity = Velocity {
+            vx: Fixed::from_ratio(1, 2),
+            vy: Fixed::from_ratio(1, 4),
+        };
+
+        game.let_ball_move();
+        assert_eq!(game.ball.position.round(), Point { x: 51, y: 50 });
+        game.let_ball_move();
+        assert_eq!(game.ball.position.round(), Point { x: 51, y: 51 });
+        game.let_ball_move();
+        game.let_ball_move();
+        assert_eq!(game.ball.position, FixedPoint::from(Point { x: 52, y: 51 }));
+    }
+
+    #[test]
+    fn paddle_returns_ball() {
+        let mut game = game();
+        game.ball.position = FixedPoint::from(Point { x: 9, y: 20 });
+        game.ball.velocity = Velocity::from_pixels(-1, 1);
+
+        assert_eq!(game.let_ball_move(), GameState::Ongoing);
+        assert_eq!(game.ball.velocity.vx, Fixed::from_int(1));
+    }
+
+    fn hit_left_paddle(game: &mut Game) {
+        game.ball.position = FixedPoint::from(Point { x: 12, y: 20 });
+        game.ball.velocity.vx = -game.ball.velocity.vx.abs();
+        while game.ball.velocity.vx < Fixed::ZERO {
+            assert_eq!(game.let_ball_move(), GameState::Ongoing);
+        }
+    }
+
+    #[test]
+    fn ball_speeds_up_during_rally() {
+        let mut game = GameBuilder::new(160, 128)
+            .paddle_size(Size {
+                width: 6,
+                height: 40,
+            })
+            .time_tick(TimeTick {
+                max_ball_movement: 3,
+                max_paddle_movement: 5,
+                time_step: 1,
+            })
+            .initial_ball_velocity(Velocity::from_pixels(1, 0))
+            .ball_speed_up(SpeedUp::Linear {
+                hits_per_increase: 2,
+            })
+            .build();
+        game.start_new_game();
+
+        let mut speeds = [0; 6];
+        for speed in speeds.iter_mut() {
+            hit_left_paddle(&mut game);
+            *speed = game.ball.velocity.vx.round();
+        }
+        // Capped by max_ball_movement.
+        assert_eq!(speeds, [1, 2, 2, 3, 3, 3]);
+
+        // The cap is per tick, longer frames don't slow the ball down.
+        game.set_time_step(2);
+        hit_left_paddle(&mut game);
+        assert_eq!(game.ball.velocity.vx, Fixed::from_int(3));
+
+        game.start_new_game();
+        assert_eq!(game.ball.velocity.vx, Fixed::from_int(1));
+
+        // Steep English is capped too.
+        game.ball.velocity = Velocity::from_pixels(1, -10);
+        game.hit_by(LeftRightPosition::Left);
+        assert_eq!(game.ball.velocity, Velocity::from_pixels(1, -3));
+    }
+
+    #[test]
+    fn ball_keeps_speed_by_default() {
+        let mut game = game();
+        game.start_new_game();
+        for _ in 0..5 {
+            hit_left_paddle(&mut game);
+        }
+        assert_eq!(game.ball.velocity.vx, Fixed::from_int(1));
+    }
+
+    #[test]
+    fn paddle_moves_down() {
+        let mut game = game();
+        game.move_paddle(
+            &LeftRightPosition::Left,
+            &PaddleRow::Front,
+            InpuDirection::Down,
+        );
+        assert_eq!(
+            front_paddle(&game, LeftRightPosition::Left)
+                .top_left_pos
+                .round(),
+            Point { x: 0, y: 5 }
+        );
+        assert_eq!(
+            front_paddle(&game, LeftRightPosition::Right)
+                .top_left_pos
+                .round(),
+            Point { x: 154, y: 0 }
+        );
+    }
+
+    #[test]
+    fn paddle_movement_scales_with_time_step() {
+        let mut game = game();
+        game.set_time_step(2);
+        game.move_paddle(
+            &LeftRightPosition::Left,
+            &PaddleRow::Front,
+            InpuDirection::Down,
+        );
+        assert_eq!(
+            front_paddle(&game, LeftRightPosition::Left)
+                .top_left_pos
+                .round(),
+            Point { x: 0, y: 10 }
+        );
+
+        game.set_time_step(0);
+        game.move_paddle(
+            &LeftRightPosition::Left,
+            &PaddleRow::Front,
+            InpuDirection::Down,
+        );
+        assert_eq!(
+            front_paddle(&game, LeftRightPosition::Left)
+                .top_left_pos
+                .round(),
+            Point { x: 0, y: 10 }
+        );
+    }
+
+    #[test]
+    fn paddle_speed_scales_with_input() {
+        let mut game = game();
+        game.move_paddle_at_speed(
+            &LeftRightPosition::Left,
+            &PaddleRow::Front,
+            InpuDirection::Down,
+            Fixed::from_ratio(1, 2),
+        );
+        assert_eq!(
+            front_paddle(&game, LeftRightPosition::Left).y_movement,
+            Fixed::from_ratio(5, 2)
+        );
+        game.move_paddle_at_speed(
+            &LeftRightPosition::Left,
+            &PaddleRow::Front,
+            InpuDirection::Down,
+            Fixed::from_int(3),
+        );
+        assert_eq!(
+            front_paddle(&game, LeftRightPosition::Left).y_movement,
+            Fixed::from_int(5)
+        );
+        game.move_paddle_at_speed(
+            &LeftRightPosition::Left,
+            &PaddleRow::Front,
+            InpuDirection::Up,
+            Fixed::ZERO,
+        );
+        assert_eq!(
+            front_paddle(&game, LeftRightPosition::Left).y_movement,
+            Fixed::ZERO
+        );
+    }
+
+    #[test]
+    fn paddle_moves_to_position() {
+        let mut game = game();
+        game.move_paddle_to(&LeftRightPosition::Right, &PaddleRow::Front, 100);
+        assert_eq!(
+            front_paddle(&game, LeftRightPosition::Right)
+                .top_left_pos
+                .round(),
+            Point { x: 154, y: 88 }
+        );
+        assert_eq!(
+            front_paddle(&game, LeftRightPosition::Right).y_movement,
+            Fixed::from_int(88)
+        );
+        game.move_paddle_to(&LeftRightPosition::Right, &PaddleRow::Front, 25);
+        assert_eq!(
+            front_paddle(&game, LeftRightPosition::Right)
+                .top_left_pos
+                .round(),
+            Point { x: 154, y: 22 }
+        );
+        game.move_paddle_to(&LeftRightPosition::Right, &PaddleRow::Front, 25);
+        assert_eq!(
+            front_paddle(&game, LeftRightPosition::Right).y_movement,
+            Fixed::ZERO
+        );
+        assert_eq!(
+            front_paddle(&game, LeftRightPosition::Left)
+                .top_left_pos
+                .round(),
+            Point { x: 0, y: 0 }
+        );
+    }
+
+    #[test]
+    fn paddle_moves_up() {
+        let mut game = game();
+        game.move_paddle(
+            &LeftRightPosition::Right,
+            &PaddleRow::Front,
+            InpuDirection::Down,
+        );
+        game.move_paddle(
+            &LeftRightPosition::Right,
+            &PaddleRow::Front,
+            InpuDirection::Down,
+        );
+        game.move_paddle(
+            &LeftRightPosition::Right,
+            &PaddleRow::Front,
+            InpuDirection::Up,
+        );
+        assert_eq!(
+            front_paddle(&game, LeftRightPosition::Right)
+                .top_left_pos
+                .round(),
+            Point { x: 154, y: 5 }
+        );
+    }
+
+    #[test]
+    fn paddle_remembers_latest_movement() {
+        let mut game = game();
+        game.move_paddle(
+            &LeftRightPosition::Left,
+            &PaddleRow::Front,
+            InpuDirection::Down,
+        );
+        assert_eq!(
+            front_paddle(&game, LeftRightPosition::Left).y_movement,
+            Fixed::from_int(5)
+        );
+        game.move_paddle(
+            &LeftRightPosition::Left,
+            &PaddleRow::Front,
+            InpuDirection::Stay,
+        );
+        assert_eq!(
+            front_paddle(&game, LeftRightPosition::Left).y_movement,
+            Fixed::from_int(0)
+        );
+        game.move_paddle(
+            &LeftRightPosition::Left,
+            &PaddleRow::Front,
+            InpuDirection::Up,
+        );
+        game.move_paddle(
+            &LeftRightPosition::Left,
+            &PaddleRow::Front,
+            InpuDirection::Up,
+        );
+        assert_eq!(
+            front_paddle(&game, LeftRightPosition::Left).y_movement,
+            Fixed::from_int(0)
+        );
+    }
+
+    #[test]
+    fn paddle_stays() {
+        let mut game = game();
+        game.reset_position_update_indicators();
+        game.move_paddle(
+            &LeftRightPosition::Left,
+            &PaddleRow::Front,
+            InpuDirection::Stay,
+        );
+        assert_eq!(
+            front_paddle(&game, LeftRightPosition::Left)
+                .top_left_pos
+                .round(),
+            Point { x: 0, y: 0 }
+        );
+        assert!(!front_paddle(&game, LeftRightPosition::Left).has_moved);
+    }
+
+    #[test]
+    fn paddle_stays_on_screen() {
+        let mut game = game();
+        game.move_paddle(
+            &LeftRightPosition::Left,
+            &PaddleRow::Front,
+            InpuDirection::Up,
+        );
+        assert_eq!(
+            front_paddle(&game, LeftRightPosition::Left)
+                .top_left_pos
+                .round(),
+            Point { x: 0, y: 0 }
+        );
+
+        for _ in 0..100 {
+            game.move_paddle(
+                &LeftRightPosition::Left,
+                &PaddleRow::Front,
+                InpuDirection::Down,
+            );
+        }
+        assert_eq!(
+            front_paddle(&game, LeftRightPosition::Left)
+                .top_left_pos
+                .round(),
+            Point { x: 0, y: 85 }
+        );
+    }
+
+    fn as_text(object: &ScreenObject) -> &ScreenText {
+        match object {
+            ScreenObject::Text(text) => text,
+            _ => panic!("Expected text, got {:?}", object),
+        }
+    }
+
+    #[test]
+    fn score_is_part_of_displayed_content() {
+        let mut game = game();
+        miss_on_right_side(&mut game);
+
+        let content = game.get_content_to_display();
+        let court_lines = game.get_court_content().len();
+        assert_eq!(content.len(), court_lines + 5);
+        let left_score = as_text(&content[court_lines]);
+        assert_eq!(left_score.content.as_str(), "1");
+        assert_eq!(left_score.position, Point { x: 40, y: 6 });
+        let right_score = as_text(&content[court_lines + 1]);
+        assert_eq!(right_score.content.as_str(), "0");
+        assert_eq!(right_score.position, Point { x: 120, y: 6 });
+    }
+
+    fn court_lines(game: &Game) -> Vec<Rectangle, COURT_CAPACITY> {
+        game.get_court_content()
+            .iter()
+            .map(|object| match object {
+                ScreenObject::CourtMarking(line) => *line,
+                _ => panic!("Expected a court marking, got {:?}", object),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn center_line_is_dashed_down_the_middle() {
+        let dashes = court_lines(&game());
+        assert_eq!(dashes.len(), 16);
+        assert_eq!(
+            dashes[0],
+            Rectangle::new(Point { x: 79, y: 2 }, Size::new(2, 4))
+        );
+        assert_eq!(
+            dashes[15],
+            Rectangle::new(Point { x: 79, y: 122 }, Size::new(2, 4))
+        );
+    }
+
+    #[test]
+    fn court_markings_are_optional() {
+        let with_markings = |court_markings| {
+            GameBuilder::new(160, 128)
+                .paddle_size(Size {
+                    width: 6,
+                    height: 40,
+                })
+                .court_markings(court_markings)
+                .build()
+        };
+        let nothing = with_markings(CourtMarkings {
+            center_line: false,
+            borders: false,
+            goal_zones: false,
+        });
+        assert!(court_lines(&nothing).is_empty());
+
+        let borders_and_goals = with_markings(CourtMarkings {
+            center_line: false,
+            borders: true,
+            goal_zones: true,
+        });
+        assert_eq!(
+            court_lines(&borders_and_goals).as_slice(),
+            &[
+                Rectangle::new(Point { x: 0, y: 0 }, Size::new(160, 1)),
+                Rectangle::new(Point { x: 0, y: 127 }, Size::new(160, 1)),
+                Rectangle::new(Point { x: 6, y: 0 }, Size::new(1, 128)),
+                Rectangle::new(Point { x: 153, y: 0 }, Size::new(1, 128)),
+            ]
+        );
+    }
+
+    fn four_player_game() -> Game {
+        GameBuilder::new(160, 128)
+            .ball_radius(3)
+            .paddle_size(Size {
+                width: 6,
+                height: 40,
+            })
+            .time_tick(TimeTick {
+                max_ball_movement: 5,
+                max_paddle_movement: 5,
+                time_step: 1,
+            })
+            .initial_ball_velocity(Velocity::from_pixels(1, 1))
+            .court_markings(CourtMarkings {
+                center_line: true,
+                borders: true,
+                goal_zones: true,
+            })
+            .four_players(true)
+            .build()
+    }
+
+    #[test]
+    fn four_players_have_paddles_on_all_walls() {
+        let two_players = game();
+        let game = four_player_game();
+        assert_eq!(
+            front_paddle(&game, LeftRightPosition::Left)
+                .top_left_pos
+                .round(),
+            Point { x: 0, y: 6 }
+        );
+        assert_eq!(
+            front_paddle(&game, LeftRightPosition::Right)
+                .top_left_pos
+                .round(),
+            Point { x: 154, y: 6 }
+        );
+        let top_paddle = game.get_paddle(&LeftRightPosition::Top).unwrap();
+        assert_eq!(top_paddle.top_left_pos.round(), Point { x: 6, y: 0 });
+        assert_eq!((top_paddle.x_size, top_paddle.y_size), (40, 6));
+        let bottom_paddle = game.get_paddle(&LeftRightPosition::Bottom).unwrap();
+        assert_eq!(bottom_paddle.top_left_pos.round(), Point { x: 6, y: 122 });
+        assert_eq!(game.get_sides().len(), 4);
+
+        assert!(two_players.get_paddle(&LeftRightPosition::Top).is_none());
+        assert_eq!(
+            two_players.get_sides().as_slice(),
+            &[LeftRightPosition::Left, LeftRightPosition::Right]
+        );
+    }
+
+    #[test]
+    fn paddle_shapes_know_their_side() {
+        let mut game = four_player_game();
+        let owners: Vec<LeftRightPosition, 4> = game
+            .get_content_to_display()
+            .iter()
+            .filter_map(|object| match object {
+                ScreenObject::Paddle(_, side) => Some(*side),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(owners.as_slice(), game.get_sides().as_slice());
+    }
+
+    #[test]
+    fn top_and_bottom_paddles_move_sideways() {
+        let mut game = four_player_game();
+        game.move_paddle(
+            &LeftRightPosition::Top,
+            &PaddleRow::Front,
+            InpuDirection::Down,
+        );
+        assert_eq!(
+            front_paddle(&game, LeftRightPosition::Top)
+                .top_left_pos
+                .round(),
+            Point { x: 11, y: 0 }
+        );
+        assert_eq!(
+            front_paddle(&game, LeftRightPosition::Top).y_movement,
+            Fixed::from_int(5)
+        );
+
+        // The corners belong to nobody.
+        game.move_paddle(
+            &LeftRightPosition::Bottom,
+            &PaddleRow::Front,
+            InpuDirection::Up,
+        );
+        assert_eq!(
+            front_paddle(&game, LeftRightPosition::Bottom)
+                .top_left_pos
+                .round(),
+            Point { x: 6, y: 122 }
+        );
+        game.move_paddle_to(&LeftRightPosition::Bottom, &PaddleRow::Front, 100);
+        assert_eq!(
+            front_paddle(&game, LeftRightPosition::Bottom)
+                .top_left_pos
+                .round(),
+            Point { x: 114, y: 122 }
+        );
+        game.move_paddle_to(&LeftRightPosition::Left, &PaddleRow::Front, 100);
+        assert_eq!(
+            front_paddle(&game, LeftRightPosition::Left)
+                .top_left_pos
+                .round(),
+            Point { x: 0, y: 82 }
+        );
+    }
+
+    #[test]
+    fn top_and_bottom_walls_are_goals() {
+        let mut game = four_player_game();
+        game.ball.position = FixedPoint::from(Point { x: 100, y: 0 });
+        game.ball.velocity = Velocity::from_pixels(1, -1);
+
+        // Nobody hit the ball, so the point goes across the court.
+        assert_eq!(
+            game.let_ball_move(),
+            GameState::PointScored(GameOver::BottomWins)
+        );
+        assert_eq!(
+            game.get_score(),
+            Score {
+                bottom: 1,
+                ..Score::default()
+            }
+        );
+        game.start_new_game();
+        assert!(game.ball.velocity.vy < Fixed::ZERO);
+    }
+
+    #[test]
+    fn last_player_to_hit_the_ball_scores() {
+        let mut game = four_player_game();
+        game.ball.position = FixedPoint::from(Point { x: 20, y: 12 });
+        game.ball.velocity = Velocity::from_pixels(0, -1);
+        while game.ball.velocity.vy < Fixed::ZERO {
+            assert_eq!(game.let_ball_move(), GameState::Ongoing);
+        }
+
+        game.ball.position = FixedPoint::from(Point { x: 100, y: 128 });
+        assert_eq!(
+            game.let_ball_move(),
+            GameState::PointScored(GameOver::TopWins)
+        );
+    }
+
+    #[test]
+    fn four_player_court_has_goal_lines_on_all_walls() {
+        assert_eq!(
+            court_lines(&four_player_game()).as_slice(),
+            &[
+                Rectangle::new(Point { x: 6, y: 0 }, Size::new(1, 128)),
+                Rectangle::new(Point { x: 153, y: 0 }, Size::new(1, 128)),
+                Rectangle::new(Point { x: 0, y: 6 }, Size::new(160, 1)),
+                Rectangle::new(Point { x: 0, y: 121 }, Size::new(160, 1)),
+            ]
+        );
+    }
+
+    fn doubles_game() -> Game {
+        GameBuilder::new(160, 128)
+            .ball_radius(3)
+            .paddle_size(Size {
+                width: 6,
+                height: 40,
+            })
+            .time_tick(TimeTick {
+                max_ball_movement: 5,
+                max_paddle_movement: 5,
+                time_step: 1,
+            })
+            .court_markings(CourtMarkings {
+                center_line: false,
+                borders: false,
+                goal_zones: true,
+            })
+            .doubles(true)
+            .build()
+    }
+
+    fn paddle_positions(game: &Game, side: LeftRightPosition) -> Vec<Point, PADDLES_PER_SIDE> {
+        game.get_paddles(&side)
+            .iter()
+            .map(|paddle| paddle.top_left_pos.round())
+            .collect()
+    }
+
+    #[test]
+    fn doubles_have_front_and_back_paddles() {
+        let game = doubles_game();
+        assert_eq!(
+            paddle_positions(&game, LeftRightPosition::Left).as_slice(),
+            &[Point { x: 40, y: 0 }, Point { x: 0, y: 0 }]
+        );
+        assert_eq!(
+            paddle_positions(&game, LeftRightPosition::Right).as_slice(),
+            &[Point { x: 114, y: 0 }, Point { x: 154, y: 0 }]
+        );
+        // The goal lines are at the back paddles, the front ones play in front of them.
+        assert_eq!(
+            court_lines(&game).as_slice(),
+            &[
+                Rectangle::new(Point { x: 6, y: 0 }, Size::new(1, 128)),
+                Rectangle::new(Point { x: 153, y: 0 }, Size::new(1, 128)),
+            ]
+        );
+        assert_eq!(game.get_paddles(&LeftRightPosition::Left).len(), 2);
+        assert_eq!(game.get_paddles(&LeftRightPosition::Top).len(), 0);
+    }
+
+    #[test]
+    fn largest_game_fits_on_screen() {
+        let mut game = doubles_game();
+        game.court_markings = CourtMarkings {
+            center_line: true,
+            borders: true,
+            goal_zones: true,
+        };
+        assert_eq!(game.get_content_to_display().len(), 20 + 2 + 4 + 1);
+
+        let mut game = GameBuilder::new(160, 128)
+            .four_players(true)
+            .doubles(true)
+            .court_markings(game.court_markings)
+            .build();
+        assert_eq!(game.get_content_to_display().len(), 4 + 4 + 8 + 1);
+    }
+
+    #[test]
+    fn doubles_paddles_move_on_their_own() {
+        let mut game = doubles_game();
+        game.move_paddle(
+            &LeftRightPosition::Left,
+            &PaddleRow::Back,
+            InpuDirection::Down,
+        );
+        game.move_paddle_to(&LeftRightPosition::Right, &PaddleRow::Front, 100);
+        assert_eq!(
+            paddle_positions(&game, LeftRightPosition::Left).as_slice(),
+            &[Point { x: 40, y: 0 }, Point { x: 0, y: 5 }]
+        );
+        assert_eq!(
+            paddle_positions(&game, LeftRightPosition::Right).as_slice(),
+            &[Point { x: 114, y: 88 }, Point { x: 154, y: 0 }]
+        );
+    }
+
+    /// Where the ball turns back when sent from `(x, y)` towards the left.
+    fn bounce_back_from(game: &mut Game, x: i32, y: i32) -> i32 {
+        game.ball.position = FixedPoint::from(Point { x, y });
+        game.ball.velocity = Velocity::from_pixels(-5, 0);
+        while game.ball.velocity.vx < Fixed::ZERO {
+            assert_eq!(game.let_ball_move(), GameState::Ongoing);
+        }
+        game.ball.position.round().x
+    }
+
+    #[test]
+    fn ball_bounces_off_whichever_doubles_paddle_is_in_the_way() {
+        let mut game = doubles_game();
+        // Touches the front paddle at x = 48 and goes back three pixels in the same tick.
+        assert_eq!(bounce_back_from(&mut game, 60, 20), 51);
+        // Below the front paddle only the back one is in the way.
+        game.move_paddle_to(&LeftRightPosition::Left, &PaddleRow::Back, 100);
+        assert_eq!(bounce_back_from(&mut game, 60, 100), 11);
+    }
+
+    #[test]
+    fn message_is_centered() {
+        let game = game();
+        let message = game.get_message_content("LEFT WINS");
+        let message = as_text(&message);
+        assert_eq!(message.content.as_str(), "LEFT WINS");
+        assert_eq!(message.position, Point { x: 79, y: 63 });
+        assert_eq!(message.alignment, Alignment::Center);
+    }
+
+    #[test]
+    fn long_text_is_cut_off() {
+        let text = ScreenText::new(
+            Point { x: 0, y: 0 },
+            "THIS IS FAR TOO LONG",
+            Alignment::Left,
+        );
+        assert_eq!(text.content.as_str(), "THIS IS FAR TOO ");
+    }
+
+    #[test]
+    fn only_moved_content_is_reported() {
+        let mut game = game();
+        game.reset_position_update_indicators();
+        assert!(game.get_moved_content().is_empty());
+
+        game.move_paddle(
+            &LeftRightPosition::Left,
+            &PaddleRow::Front,
+            InpuDirection::Down,
+        );
+        assert_eq!(game.get_moved_content().len(), 1);
+    }
+}
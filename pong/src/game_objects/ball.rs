@@ -0,0 +1,671 @@
+use embedded_graphics::{
+    prelude::{Point, Size},
+    primitives::{self, Rectangle},
+};
+use heapless::Vec;
+
+use crate::input::LeftRightPosition;
+use crate::physics::{
+    time_of_impact, Bounce, Fixed, FixedPoint, MovingObject, Obstacles, PaddleBounce, Velocity,
+};
+
+use super::{
+    super::physics::{BouncableObject, TimeTick},
+    paddle::Paddle,
+};
+
+use super::{GameObject, ScreenObject};
+
+/// Most bounces the ball makes in one move. A ball that would bounce more often stops where the
+/// last one left it, instead of going through anything.
+pub const MAX_BOUNCES_PER_MOVE: usize = 8;
+
+#[derive(Clone, Copy, Debug)]
+pub struct Ball {
+    /// Center of the ball.
+    pub position: FixedPoint,
+    pub radius: u32,
+    pub velocity: Velocity,
+    pub has_moved: bool,
+}
+
+impl Default for Ball {
+    fn default() -> Self {
+        Ball {
+            position: FixedPoint::default(),
+            radius: 1,
+            velocity: Velocity::default(),
+            has_moved: false,
+        }
+    }
+}
+
+impl GameObject for Ball {
+    fn set_position(&self, pos: Point) -> Self {
+        Self {
+            position: FixedPoint::from(pos),
+            radius: self.radius,
+            velocity: self.velocity,
+            has_moved: self.has_moved,
+        }
+    }
+    fn as_shapes(&self) -> Vec<ScreenObject, 2> {
+        let mut shapes: Vec<ScreenObject, 2> = Vec::new();
+        shapes
+            .push(ScreenObject::Circle(primitives::Circle::with_center(
+                self.position.round(),
+                self.get_diameter(),
+            )))
+            .unwrap();
+        shapes
+    }
+    fn get_box_covering_object(&self) -> Rectangle {
+        let center = self.position.round();
+        let top_left = Point {
+            x: center.x - self.radius as i32,
+            y: center.y - self.radius as i32,
+        };
+        let diameter = self.get_diameter();
+        Rectangle {
+            top_left,
+            size: Size {
+                width: diameter,
+                height: diameter,
+            },
+        }
+    }
+    fn is_within(&self, rectange: &Rectangle) -> bool {
+        // TODO: refactor into class?
+        let box_covering_object = self.get_box_covering_object();
+        let considered_corners = [
+            box_covering_object.top_left,
+            box_covering_object.bottom_right().unwrap(),
+        ];
+
+        considered_corners
+            .iter()
+            .all(|corner| rectange.contains(*corner))
+    }
+}
+
+impl BouncableObject for Ball {
+    fn bounce_off_first_obstacle(
+        &mut self,
+        movement: &FixedPoint,
+        obstacles: &Obstacles,
+        paddle_bounce: &PaddleBounce,
+    ) -> Option<Bounce> {
+        let wall = obstacles
+            .walls
+            .and_then(|screen| self.time_to_reach_wall(&screen, movement))
+            .map(|time| (time, None));
+        let radius = Fixed::from_int(self.radius as i32);
+        // In doubles the ball may pass one paddle and reach the other, it bounces off whichever
+        // it reaches first.
+        let paddles = obstacles
+            .paddles
+            .iter()
+            .filter(|(side, _)| self.is_heading_for(side))
+            .flat_map(|(side, paddles)| paddles.iter().map(move |paddle| (side, paddle)))
+            .filter_map(|(side, paddle)| {
+                let paddle_area = paddle.get_box_covering_object();
+                time_of_impact(self.position, *movement, radius, &paddle_area)
+                    .map(|time| (time, Some((*side, paddle))))
+            });
+        let first_obstacle = wall
+            .into_iter()
+            .chain(paddles)
+            .min_by_key(|(time, _)| *time);
+
+        let (time, paddle) = match first_obstacle {
+            Some(obstacle) => obstacle,
+            None => {
+                self.position = self.position + *movement;
+                return None;
+            }
+        };
+        self.position = self.position
+            + FixedPoint {
+                x: movement.x * time,
+                y: movement.y * time,
+            };
+        match paddle {
+            Some((side, paddle)) => self.bounce_off_paddle_on(&side, paddle, paddle_bounce),
+            None => self.invert_vertical_velocity(),
+        }
+        Some(Bounce {
+            time,
+            paddle: paddle.map(|(side, _)| side),
+        })
+    }
+}
+
+impl MovingObject for Ball {
+    fn get_velocity(&self) -> Velocity {
+        self.velocity
+    }
+    fn get_relative_movement(&self, time: &TimeTick) -> FixedPoint {
+        let velocity = self.get_velocity();
+        let dx = velocity.vx * (time.time_step as i32);
+        let dy = velocity.vy * (time.time_step as i32);
+        FixedPoint { x: dx, y: dy }
+    }
+}
+
+impl Ball {
+    pub fn left_player_has_lost_ball(&self, screen: &Rectangle) -> bool {
+        self.position.x < Fixed::from_int(screen.top_left.x)
+    }
+    pub fn right_player_has_lost_ball(&self, screen: &Rectangle) -> bool {
+        self.position.x > Fixed::from_int(screen.top_left.x + (screen.size.width as i32))
+    }
+    pub fn top_player_has_lost_ball(&self, screen: &Rectangle) -> bool {
+        self.position.y < Fixed::from_int(screen.top_left.y)
+    }
+    pub fn bottom_player_has_lost_ball(&self, screen: &Rectangle) -> bool {
+        self.position.y > Fixed::from_int(screen.top_left.y + (screen.size.height as i32))
+    }
+    /// Mirrored along the diagonal, x and y swap places.
+    pub fn transposed(&self) -> Ball {
+        Ball {
+            position: self.position.transposed(),
+            velocity: self.velocity.transposed(),
+            ..*self
+        }
+    }
+
+    /// Moves the ball on at its velocity for `ticks`, bouncing off every wall and paddle in its
+    /// way. Returns whose paddles it hit, in order.
+    pub fn move_for(
+        &mut self,
+        ticks: Fixed,
+        obstacles: &Obstacles,
+        paddle_bounce: &PaddleBounce,
+    ) -> Vec<LeftRightPosition, MAX_BOUNCES_PER_MOVE> {
+        let mut hit_paddles = Vec::new();
+        let mut ticks_left = ticks;
+        for _ in 0..MAX_BOUNCES_PER_MOVE {
+            let movement = FixedPoint {
+                x: self.velocity.vx * ticks_left,
+                y: self.velocity.vy * ticks_left,
+            };
+            let bounce = match self.bounce_off_first_obstacle(&movement, obstacles, paddle_bounce) {
+                Some(bounce) => bounce,
+                None => break,
+            };
+            if let Some(side) = bounce.paddle {
+                hit_paddles.push(side).unwrap();
+            }
+            // The rest of the time goes on at the new velocity.
+            ticks_left = ticks_left * (Fixed::ONE - bounce.time);
+        }
+        hit_paddles
+    }
+
+    fn is_heading_for(&self, side: &LeftRightPosition) -> bool {
+        match side {
+            LeftRightPosition::Left => self.velocity.vx < Fixed::ZERO,
+            LeftRightPosition::Right => self.velocity.vx > Fixed::ZERO,
+            LeftRightPosition::Top => self.velocity.vy < Fixed::ZERO,
+            LeftRightPosition::Bottom => self.velocity.vy > Fixed::ZERO,
+        }
+    }
+
+    /// Share of `movement` after which the centre of the ball reaches the top or bottom of
+    /// `screen`, if it goes past it.
+    fn time_to_reach_wall(&self, screen: &Rectangle, movement: &FixedPoint) -> Option<Fixed> {
+        let top = Fixed::from_int(screen.top_left.y);
+        let bottom = Fixed::from_int(screen.bottom_right().unwrap().y);
+        let new_height = self.position.y + movement.y;
+        let wall = if movement.y < Fixed::ZERO && new_height < top {
+            top
+        } else if movement.y > Fixed::ZERO && new_height > bottom {
+            bottom
+        } else {
+            return None;
+        };
+        Some(((wall - self.position.y) / movement.y).max(Fixed::ZERO))
+    }
+
+    fn invert_vertical_velocity(&mut self) {
+        self.velocity.vy = -self.velocity.vy;
+    }
+
+    fn invert_horizontal_velocity(&mut self) {
+        self.velocity.vx = -self.velocity.vx;
+    }
+
+    fn get_diameter(&self) -> u32 {
+        self.radius * 2 + 1
+    }
+
+    /// Seen mirrored along the diagonal, the top and bottom paddles bounce the ball like the left
+    /// and right ones.
+    fn bounce_off_paddle_on(
+        &mut self,
+        side: &LeftRightPosition,
+        paddle: &Paddle,
+        paddle_bounce: &PaddleBounce,
+    ) {
+        match side {
+            LeftRightPosition::Top | LeftRightPosition::Bottom => {
+                let mut transposed = self.transposed();
+                transposed.bounce_off_paddle(&paddle.transposed(), paddle_bounce);
+                *self = transposed.transposed();
+            }
+            LeftRightPosition::Left | LeftRightPosition::Right => {
+                self.bounce_off_paddle(paddle, paddle_bounce)
+            }
+        }
+    }
+
+    /// Bounces off the side of the paddle the ball is touching. Hitting a corner bounces in both
+    /// directions.
+    fn bounce_off_paddle(&mut self, paddle: &Paddle, paddle_bounce: &PaddleBounce) {
+        let closest_point = closest_point_in(&paddle.get_box_covering_object(), self.position);
+        let towards_paddle = closest_point - self.position;
+        let hits_side = towards_paddle.x.signum() == self.velocity.vx.signum()
+            && towards_paddle.x != Fixed::ZERO;
+        let hits_top_or_bottom = towards_paddle.y.signum() == self.velocity.vy.signum()
+            && towards_paddle.y != Fixed::ZERO;
+
+        if hits_top_or_bottom {
+            self.invert_vertical_velocity();
+        }
+        if hits_side || !hits_top_or_bottom {
+            self.invert_horizontal_velocity();
+        }
+        if hits_side && !hits_top_or_bottom {
+            self.angle_off_paddle(paddle, paddle_bounce);
+        }
+    }
+
+    fn angle_off_paddle(&mut self, paddle: &Paddle, paddle_bounce: &PaddleBounce) {
+        let max_speed = Fixed::from_int(paddle_bounce.max_vertical_speed as i32);
+        if max_speed > Fixed::ZERO {
+            let paddle_area = paddle.get_box_covering_object();
+            // The end pixels are (length - 1) / 2 from the middle.
+            let max_offset = Fixed::from_ratio((paddle.y_size as i32 - 1).max(1), 2);
+            let middle = Fixed::from_int(paddle_area.top_left.y) + max_offset;
+            let offset = self.position.y - middle;
+            self.velocity.vy = (max_speed * offset / max_offset).clamp(-max_speed, max_speed);
+        }
+        let movement_speed = Fixed::from_int(paddle_bounce.paddle_movement_speed as i32);
+        self.velocity.vy += movement_speed * paddle.y_movement.signum();
+    }
+}
+
+fn closest_point_in(area: &Rectangle, point: FixedPoint) -> FixedPoint {
+    let bottom_right = area.bottom_right().unwrap_or(area.top_left);
+    FixedPoint {
+        x: point.x.clamp(
+            Fixed::from_int(area.top_left.x),
+            Fixed::from_int(bottom_right.x),
+        ),
+        y: point.y.clamp(
+            Fixed::from_int(area.top_left.y),
+            Fixed::from_int(bottom_right.y),
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn screen() -> Rectangle {
+        Rectangle {
+            top_left: Point { x: 0, y: 0 },
+            size: Size {
+                width: 160,
+                height: 128,
+            },
+        }
+    }
+
+    fn ball_at(x: i32, y: i32, vx: i32, vy: i32) -> Ball {
+        Ball {
+            position: at(x, y),
+            radius: 3,
+            velocity: Velocity::from_pixels(vx, vy),
+            has_moved: false,
+        }
+    }
+
+    fn at(x: i32, y: i32) -> FixedPoint {
+        FixedPoint::from(Point { x, y })
+    }
+
+    fn paddle_at(x: i32, y: i32) -> Paddle {
+        Paddle {
+            top_left_pos: at(x, y),
+            x_size: 6,
+            y_size: 40,
+            has_moved: false,
+            y_movement: Fixed::ZERO,
+            side: LeftRightPosition::Left,
+        }
+    }
+
+    /// Lets `ball` move for `ticks` between the walls of `screen()` and the `left` and `right`
+    /// paddles.
+    fn move_between(
+        ball: &mut Ball,
+        ticks: i32,
+        left: &[Paddle],
+        right: &[Paddle],
+        paddle_bounce: &PaddleBounce,
+    ) -> Vec<LeftRightPosition, MAX_BOUNCES_PER_MOVE> {
+        let paddles = [
+            (LeftRightPosition::Left, Vec::from_slice(left).unwrap()),
+            (LeftRightPosition::Right, Vec::from_slice(right).unwrap()),
+        ];
+        let obstacles = Obstacles {
+            walls: Some(screen()),
+            paddles: &paddles,
+        };
+        ball.move_for(Fixed::from_int(ticks), &obstacles, paddle_bounce)
+    }
+
+    fn move_and_bounce(ball: &mut Ball, paddles: (Paddle, Paddle)) {
+        move_between(
+            ball,
+            1,
+            &[paddles.0],
+            &[paddles.1],
+            &PaddleBounce::default(),
+        );
+    }
+
+    #[test]
+    fn moves_freely_between_walls() {
+        let mut ball = ball_at(50, 50, 2, 3);
+        move_between(&mut ball, 1, &[], &[], &PaddleBounce::default());
+        assert_eq!(ball.position.round(), Point { x: 52, y: 53 });
+        assert_eq!(ball.velocity.vy, Fixed::from_int(3));
+    }
+
+    #[test]
+    fn bounces_against_top_wall() {
+        let mut ball = ball_at(50, 1, 2, -3);
+        move_between(&mut ball, 1, &[], &[], &PaddleBounce::default());
+        assert_eq!(ball.position.round(), Point { x: 52, y: 2 });
+        assert_eq!(ball.velocity.vy, Fixed::from_int(3));
+        assert_eq!(ball.velocity.vx, Fixed::from_int(2));
+    }
+
+    #[test]
+    fn bounces_against_bottom_wall() {
+        let mut ball = ball_at(50, 126, 2, 3);
+        move_between(&mut ball, 1, &[], &[], &PaddleBounce::default());
+        assert_eq!(ball.position.round(), Point { x: 52, y: 125 });
+        assert_eq!(ball.velocity.vy, Fixed::from_int(-3));
+    }
+
+    #[test]
+    fn bounces_against_left_paddle() {
+        let mut ball = ball_at(12, 20, -4, 2);
+        let hit_paddles = move_between(
+            &mut ball,
+            1,
+            &[paddle_at(0, 0)],
+            &[paddle_at(154, 80)],
+            &PaddleBounce::default(),
+        );
+        // It touches the paddle just as the tick ends.
+        assert_eq!(ball.position.round(), Point { x: 8, y: 22 });
+        assert_eq!(ball.velocity.vx, Fixed::from_int(4));
+        assert_eq!(ball.velocity.vy, Fixed::from_int(2));
+        assert_eq!(hit_paddles.as_slice(), &[LeftRightPosition::Left]);
+    }
+
+    #[test]
+    fn bounces_against_right_paddle() {
+        let mut ball = ball_at(145, 100, 6, 1);
+        move_and_bounce(&mut ball, (paddle_at(0, 0), paddle_at(154, 80)));
+        assert_eq!(ball.position.round(), Point { x: 151, y: 101 });
+        assert_eq!(ball.velocity.vx, Fixed::from_int(-6));
+    }
+
+    #[test]
+    fn bounces_against_top_paddle() {
+        // `bounces_against_left_paddle` mirrored along the diagonal.
+        let mut ball = ball_at(20, 12, 2, -4);
+        let paddles = [
+            (
+                LeftRightPosition::Top,
+                Vec::from_slice(&[paddle_at(0, 0).transposed()]).unwrap(),
+            ),
+            (
+                LeftRightPosition::Bottom,
+                Vec::from_slice(&[paddle_at(122, 80).transposed()]).unwrap(),
+            ),
+        ];
+        let obstacles = Obstacles {
+            walls: None,
+            paddles: &paddles,
+        };
+        let hit_paddles = ball.move_for(Fixed::ONE, &obstacles, &PaddleBounce::default());
+        assert_eq!(ball.position.round(), Point { x: 22, y: 8 });
+        assert_eq!(ball.velocity.vx, Fixed::from_int(2));
+        assert_eq!(ball.velocity.vy, Fixed::from_int(4));
+        assert_eq!(hit_paddles.as_slice(), &[LeftRightPosition::Top]);
+    }
+
+    #[test]
+    fn bounces_against_whichever_paddle_it_reaches_first() {
+        let front_paddle = paddle_at(40, 50);
+        let mut ball = ball_at(60, 20, -6, 0);
+        move_between(
+            &mut ball,
+            9,
+            &[front_paddle, paddle_at(0, 0)],
+            &[paddle_at(154, 80)],
+            &PaddleBounce::default(),
+        );
+        // Above the front paddle, so the back one at x = 8 sends it back 2 pixels.
+        assert_eq!(ball.position.round(), Point { x: 10, y: 20 });
+        assert_eq!(ball.velocity.vx, Fixed::from_int(6));
+
+        // In front of both, whatever order they come in.
+        let mut ball = ball_at(60, 60, -6, 0);
+        move_between(
+            &mut ball,
+            9,
+            &[paddle_at(0, 40), front_paddle],
+            &[paddle_at(154, 80)],
+            &PaddleBounce::default(),
+        );
+        // Touches the front paddle at x = 48 and covers the other 42 pixels going back.
+        assert_eq!(ball.position.round(), Point { x: 90, y: 60 });
+    }
+
+    #[test]
+    fn passes_paddle_at_other_height() {
+        let mut ball = ball_at(12, 60, -4, 2);
+        move_and_bounce(&mut ball, (paddle_at(0, 0), paddle_at(154, 80)));
+        assert_eq!(ball.position.round(), Point { x: 8, y: 62 });
+        assert_eq!(ball.velocity.vx, Fixed::from_int(-4));
+    }
+
+    #[test]
+    fn ignores_paddle_it_is_moving_away_from() {
+        let mut ball = ball_at(3, 20, 2, 1);
+        move_and_bounce(&mut ball, (paddle_at(0, 0), paddle_at(154, 80)));
+        assert_eq!(ball.velocity.vx, Fixed::from_int(2));
+    }
+
+    #[test]
+    fn bounces_against_paddle_edge() {
+        // The center misses the paddle, but the edge of the ball does not.
+        let mut ball = ball_at(12, 41, -6, 0);
+        move_and_bounce(&mut ball, (paddle_at(0, 0), paddle_at(154, 80)));
+        assert_eq!(ball.position.round(), Point { x: 8, y: 41 });
+        assert_eq!(ball.velocity.vx, Fixed::from_int(6));
+    }
+
+    #[test]
+    fn fast_ball_does_not_pass_through_paddle() {
+        let mut ball = ball_at(30, 20, -40, 0);
+        move_and_bounce(&mut ball, (paddle_at(0, 0), paddle_at(154, 80)));
+        // Touches the paddle at x = 8 and covers the other 18 pixels going back.
+        assert_eq!(ball.position.round(), Point { x: 26, y: 20 });
+        assert_eq!(ball.velocity.vx, Fixed::from_int(40));
+    }
+
+    #[test]
+    fn very_fast_ball_stays_in_court_over_long_tick() {
+        let mut ball = ball_at(100, 20, -50, 0);
+        let hit_paddles = move_between(
+            &mut ball,
+            10,
+            &[paddle_at(0, 0)],
+            &[paddle_at(154, 0)],
+            &PaddleBounce::default(),
+        );
+        // 92 pixels to the left paddle, 143 to the right one and back, and the last 122 to the
+        // right again.
+        assert_eq!(
+            hit_paddles.as_slice(),
+            &[
+                LeftRightPosition::Left,
+                LeftRightPosition::Right,
+                LeftRightPosition::Left
+            ]
+        );
+        assert_eq!(ball.position.round(), Point { x: 130, y: 20 });
+        assert!(screen().contains(ball.position.round()));
+    }
+
+    #[test]
+    fn very_fast_ball_bounces_off_walls_and_paddles_in_one_move() {
+        let mut ball = ball_at(80, 64, -30, 40);
+        let hit_paddles = move_between(
+            &mut ball,
+            10,
+            &[paddle_at(30, 20), paddle_at(0, 70)],
+            &[paddle_at(124, 70), paddle_at(154, 20)],
+            &PaddleBounce::default(),
+        );
+        assert_eq!(
+            hit_paddles.as_slice(),
+            &[
+                LeftRightPosition::Left,
+                LeftRightPosition::Right,
+                LeftRightPosition::Right,
+                LeftRightPosition::Left
+            ]
+        );
+        assert!(screen().contains(ball.position.round()));
+    }
+
+    #[test]
+    fn stops_after_too_many_bounces() {
+        // Walls 128 pixels apart and a ball going 1000 pixels a tick straight up and down.
+        let mut ball = ball_at(80, 64, 0, 1000);
+        move_between(&mut ball, 1, &[], &[], &PaddleBounce::default());
+        assert!(screen().contains(ball.position.round()));
+    }
+
+    #[test]
+    fn bounces_against_top_of_paddle() {
+        let mut ball = ball_at(4, 30, -1, 8);
+        move_and_bounce(&mut ball, (paddle_at(0, 40), paddle_at(154, 80)));
+        assert_eq!(ball.position.round(), Point { x: 3, y: 36 });
+        assert_eq!(ball.velocity.vx, Fixed::from_int(-1));
+        assert_eq!(ball.velocity.vy, Fixed::from_int(-8));
+    }
+
+    #[test]
+    fn bounces_back_from_paddle_corner() {
+        let mut ball = ball_at(12, 33, -6, 6);
+        move_and_bounce(&mut ball, (paddle_at(0, 40), paddle_at(154, 80)));
+        assert_eq!(ball.position.round(), Point { x: 8, y: 37 });
+        assert_eq!(ball.velocity.vx, Fixed::from_int(6));
+        assert_eq!(ball.velocity.vy, Fixed::from_int(-6));
+    }
+
+    const ANGLED_BOUNCE: PaddleBounce = PaddleBounce {
+        max_vertical_speed: 2,
+        paddle_movement_speed: 0,
+    };
+
+    fn angled_bounce_at_height(y: i32, vy: i32, paddle_bounce: &PaddleBounce) -> Ball {
+        let mut ball = ball_at(12, y, -4, vy);
+        move_between(
+            &mut ball,
+            1,
+            &[paddle_at(0, 40)],
+            &[paddle_at(154, 80)],
+            paddle_bounce,
+        );
+        ball
+    }
+
+    #[test]
+    fn leaves_straight_from_middle_of_paddle() {
+        let ball = angled_bounce_at_height(59, 1, &ANGLED_BOUNCE);
+        assert_eq!(ball.velocity.vx, Fixed::from_int(4));
+        assert_eq!(ball.velocity.vy.round(), 0);
+    }
+
+    #[test]
+    fn leaves_steeply_from_ends_of_paddle() {
+        let ball = angled_bounce_at_height(40, 1, &ANGLED_BOUNCE);
+        assert_eq!(ball.velocity.vx, Fixed::from_int(4));
+        assert_eq!(ball.velocity.vy.round(), -2);
+
+        let ball = angled_bounce_at_height(79, 0, &ANGLED_BOUNCE);
+        assert_eq!(ball.velocity.vy, Fixed::from_int(2));
+    }
+
+    #[test]
+    fn takes_up_paddle_movement() {
+        let paddle_bounce = PaddleBounce {
+            max_vertical_speed: 0,
+            paddle_movement_speed: 3,
+        };
+        let mut paddle = paddle_at(0, 40);
+        paddle.y_movement = Fixed::from_int(-5);
+        let mut ball = ball_at(12, 60, -4, 1);
+        move_between(
+            &mut ball,
+            1,
+            &[paddle],
+            &[paddle_at(154, 80)],
+            &paddle_bounce,
+        );
+        assert_eq!(ball.velocity.vx, Fixed::from_int(4));
+        assert_eq!(ball.velocity.vy, Fixed::from_int(-2));
+    }
+
+    #[test]
+    fn keeps_incoming_angle_by_default() {
+        let ball = angled_bounce_at_height(40, 1, &PaddleBounce::default());
+        assert_eq!(ball.velocity.vy, Fixed::from_int(1));
+    }
+
+    #[test]
+    fn is_drawn_around_its_center() {
+        let shapes = ball_at(50, 50, 0, 0).as_shapes();
+        match &shapes[0] {
+            ScreenObject::Circle(circle) => {
+                assert_eq!(circle.center(), Point { x: 50, y: 50 });
+                assert_eq!(circle.diameter, 7);
+            }
+            other => panic!("Expected a circle, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn detects_lost_balls() {
+        assert!(ball_at(-1, 50, -1, 0).left_player_has_lost_ball(&screen()));
+        assert!(!ball_at(0, 50, -1, 0).left_player_has_lost_ball(&screen()));
+        assert!(ball_at(161, 50, 1, 0).right_player_has_lost_ball(&screen()));
+        assert!(!ball_at(160, 50, 1, 0).right_player_has_lost_ball(&screen()));
+        assert!(ball_at(50, -1, 0, -1).top_player_has_lost_ball(&screen()));
+        assert!(!ball_at(50, 0, 0, -1).top_player_has_lost_ball(&screen()));
+        assert!(ball_at(50, 129, 0, 1).bottom_player_has_lost_ball(&screen()));
+        assert!(!ball_at(50, 128, 0, 1).bottom_player_has_lost_ball(&screen()));
+    }
+}
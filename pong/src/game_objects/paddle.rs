@@ -4,23 +4,45 @@ use embedded_graphics::{
 };
 use heapless::Vec;
 
+use crate::input::LeftRightPosition;
+use crate::physics::{Fixed, FixedPoint};
+
 use super::{GameObject, ScreenObject};
 
 #[derive(Clone, Copy, Debug)]
 pub struct Paddle {
-    pub top_left_pos: Point,
+    pub top_left_pos: FixedPoint,
     pub x_size: u32,
     pub y_size: u32,
     pub has_moved: bool,
+    /// Vertical distance moved in the latest `Game::move_paddle`, the horizontal one for the
+    /// paddles on the top and bottom walls.
+    pub y_movement: Fixed,
+    /// The wall the paddle defends, which picks its colour.
+    pub side: LeftRightPosition,
 }
 
 impl Default for Paddle {
     fn default() -> Self {
         Paddle {
-            top_left_pos: Point { x: 0, y: 0 },
+            top_left_pos: FixedPoint::default(),
             y_size: 1,
             x_size: 1,
             has_moved: false,
+            y_movement: Fixed::ZERO,
+            side: LeftRightPosition::Left,
+        }
+    }
+}
+
+impl Paddle {
+    /// Mirrored along the diagonal, which turns a top or bottom paddle into a left or right one.
+    pub fn transposed(&self) -> Paddle {
+        Paddle {
+            top_left_pos: self.top_left_pos.transposed(),
+            x_size: self.y_size,
+            y_size: self.x_size,
+            ..*self
         }
     }
 }
@@ -28,28 +50,27 @@ impl Default for Paddle {
 impl GameObject for Paddle {
     fn set_position(&self, pos: Point) -> Self {
         Self {
-            top_left_pos: pos,
+            top_left_pos: FixedPoint::from(pos),
             y_size: self.y_size,
             x_size: self.x_size,
             has_moved: self.has_moved,
+            y_movement: self.y_movement,
+            side: self.side,
         }
     }
     fn as_shapes(&self) -> Vec<ScreenObject, 2> {
         let mut shapes: Vec<ScreenObject, 2> = Vec::new();
         shapes
-            .push(ScreenObject::Rectangle(primitives::Rectangle {
-                top_left: self.top_left_pos,
-                size: Size {
-                    width: self.x_size,
-                    height: self.y_size,
-                },
-            }))
+            .push(ScreenObject::Paddle(
+                self.get_box_covering_object(),
+                self.side,
+            ))
             .unwrap();
         shapes
     }
     fn get_box_covering_object(&self) -> Rectangle {
-        Rectangle {
-            top_left: self.top_left_pos,
+        primitives::Rectangle {
+            top_left: self.top_left_pos.round(),
             size: Size {
                 width: self.x_size,
                 height: self.y_size,
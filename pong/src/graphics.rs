@@ -0,0 +1,564 @@
+use core::fmt::Debug;
+
+use embedded_graphics::draw_target::DrawTarget;
+use embedded_graphics::mono_font::{ascii::FONT_6X10, MonoTextStyle};
+use embedded_graphics::primitives::{ContainsPoint, Primitive, PrimitiveStyle, Rectangle};
+use embedded_graphics::text::{Baseline, Text, TextStyleBuilder};
+use embedded_graphics::Drawable;
+use embedded_graphics::{pixelcolor::Rgb565, prelude::*};
+use heapless::Vec;
+
+use super::game_objects::{ScreenObject, ScreenText, CONTENT_CAPACITY};
+use super::input::LeftRightPosition;
+
+/// Areas a redraw may paint over: up to four uncovered parts of every old object and every new
+/// object once.
+const ERASED_AREAS_CAPACITY: usize = CONTENT_CAPACITY * 5;
+
+/// Draws the game on anything embedded-graphics can draw on, e.g. the ST7735 on the board or a
+/// simulated screen on the host.
+pub struct Display<'a, D: DrawTarget<Color = Rgb565>> {
+    pub display: &'a mut D,
+    pub theme: Theme,
+}
+
+/// The colours the game is drawn in.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Theme {
+    pub background: Rgb565,
+    pub left_paddle: Rgb565,
+    pub right_paddle: Rgb565,
+    /// Only four-player games have top and bottom paddles.
+    pub top_paddle: Rgb565,
+    pub bottom_paddle: Rgb565,
+    pub ball: Rgb565,
+    pub text: Rgb565,
+    pub net: Rgb565,
+}
+
+impl Theme {
+    pub const CLASSIC: Theme = Theme {
+        background: Rgb565::BLACK,
+        left_paddle: Rgb565::YELLOW,
+        right_paddle: Rgb565::YELLOW,
+        top_paddle: Rgb565::YELLOW,
+        bottom_paddle: Rgb565::YELLOW,
+        ball: Rgb565::GREEN,
+        text: Rgb565::WHITE,
+        net: Rgb565::new(12, 24, 12),
+    };
+    /// Everything in white on black.
+    pub const HIGH_CONTRAST: Theme = Theme {
+        background: Rgb565::BLACK,
+        left_paddle: Rgb565::WHITE,
+        right_paddle: Rgb565::WHITE,
+        top_paddle: Rgb565::WHITE,
+        bottom_paddle: Rgb565::WHITE,
+        ball: Rgb565::WHITE,
+        text: Rgb565::WHITE,
+        net: Rgb565::WHITE,
+    };
+    /// Orange, sky blue, blue, reddish purple and yellow from the Okabe-Ito palette, which stay
+    /// apart with the common kinds of colour blindness.
+    pub const COLOR_BLIND: Theme = Theme {
+        background: Rgb565::BLACK,
+        left_paddle: Rgb565::new(28, 39, 0),
+        right_paddle: Rgb565::new(10, 45, 29),
+        top_paddle: Rgb565::new(0, 28, 22),
+        bottom_paddle: Rgb565::new(25, 30, 20),
+        ball: Rgb565::new(30, 57, 8),
+        text: Rgb565::WHITE,
+        net: Rgb565::new(12, 24, 12),
+    };
+    /// Dark on light.
+    pub const PAPER: Theme = Theme {
+        background: Rgb565::WHITE,
+        left_paddle: Rgb565::BLACK,
+        right_paddle: Rgb565::BLACK,
+        top_paddle: Rgb565::BLACK,
+        bottom_paddle: Rgb565::BLACK,
+        ball: Rgb565::new(0, 28, 22),
+        text: Rgb565::BLACK,
+        net: Rgb565::new(20, 40, 20),
+    };
+    pub const BUILT_IN: [Theme; 4] = [
+        Theme::CLASSIC,
+        Theme::HIGH_CONTRAST,
+        Theme::COLOR_BLIND,
+        Theme::PAPER,
+    ];
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Theme::CLASSIC
+    }
+}
+
+pub trait Graphics {
+    fn clear(&mut self, objects: &Vec<ScreenObject, CONTENT_CAPACITY>);
+    fn draw(&mut self, objects: &Vec<ScreenObject, CONTENT_CAPACITY>);
+    /// Takes the screen from showing `previous` to showing `current`, drawing only the objects
+    /// that changed and erasing only what they no longer cover. This pays off when both list the
+    /// same objects in the same order, as `Game::get_content_to_display` does, objects that don't
+    /// line up are simply erased and drawn again.
+    fn redraw(
+        &mut self,
+        previous: &Vec<ScreenObject, CONTENT_CAPACITY>,
+        current: &Vec<ScreenObject, CONTENT_CAPACITY>,
+    );
+    /// Switches colours and fills the screen with the new background, everything has to be drawn
+    /// again afterwards.
+    fn set_theme(&mut self, theme: Theme);
+}
+
+struct ObjectColors {
+    left_paddle_color: Rgb565,
+    right_paddle_color: Rgb565,
+    top_paddle_color: Rgb565,
+    bottom_paddle_color: Rgb565,
+    ball_color: Rgb565,
+    text_color: Rgb565,
+    net_color: Rgb565,
+}
+
+impl<'a, D: DrawTarget<Color = Rgb565>> Graphics for Display<'a, D>
+where
+    D::Error: Debug,
+{
+    fn clear(&mut self, objects: &Vec<ScreenObject, CONTENT_CAPACITY>) {
+        self.draw_objects_in_colors(objects, self.get_clear_object_colors());
+    }
+    fn draw(&mut self, objects: &Vec<ScreenObject, CONTENT_CAPACITY>) {
+        self.draw_objects_in_colors(objects, self.get_object_colors());
+    }
+    fn redraw(
+        &mut self,
+        previous: &Vec<ScreenObject, CONTENT_CAPACITY>,
+        current: &Vec<ScreenObject, CONTENT_CAPACITY>,
+    ) {
+        let clear_colors = self.get_clear_object_colors();
+        // Everything painted over so far, whatever lies there has to be drawn again.
+        let mut erased_areas: Vec<Rectangle, ERASED_AREAS_CAPACITY> = Vec::new();
+        for (index, old_object) in previous.iter().enumerate() {
+            let new_object = current.get(index);
+            if new_object == Some(old_object) {
+                continue;
+            }
+            match new_object {
+                Some(new_object) if is_shape(old_object) && is_shape(new_object) => {
+                    let uncovered =
+                        uncovered_parts(&covered_area(old_object), &covered_area(new_object));
+                    for area in uncovered.iter() {
+                        self.display
+                            .fill_solid(area, self.get_background_color())
+                            .unwrap();
+                    }
+                    erased_areas.extend(uncovered);
+                }
+                _ => {
+                    self.draw_object_in_colors(old_object, &clear_colors);
+                    erased_areas.push(covered_area(old_object)).unwrap();
+                }
+            }
+        }
+
+        let colors = self.get_object_colors();
+        // Circles are drawn together with the background around them in one go, which also
+        // erases whatever part of their old position the new bounding box covers. That may paint
+        // over a neighbour, so they go first and the neighbour is drawn again below.
+        for (index, object) in current.iter().enumerate() {
+            if let ScreenObject::Circle(circle) = object {
+                if needs_redraw(previous, index, object, &erased_areas) {
+                    let area = covered_area(object);
+                    let background = self.get_background_color();
+                    let pixels = area.points().map(|point| match circle.contains(point) {
+                        true => colors.ball_color,
+                        false => background,
+                    });
+                    self.display.fill_contiguous(&area, pixels).unwrap();
+                    erased_areas.push(area).unwrap();
+                }
+            }
+        }
+        // In list order, so whatever comes later and overlaps a drawn object stays on top of it.
+        let drawn_from = erased_areas.len();
+        for (index, object) in current.iter().enumerate() {
+            if matches!(object, ScreenObject::Circle(_)) {
+                continue;
+            }
+            if needs_redraw(previous, index, object, &erased_areas) {
+                self.draw_object_in_colors(object, &colors);
+                erased_areas.push(covered_area(object)).unwrap();
+            }
+        }
+        // The ball comes last and stays on top of e.g. the centre line it was drawn again over.
+        for object in current.iter() {
+            if let ScreenObject::Circle(circle) = object {
+                let area = circle.bounding_box();
+                if erased_areas[drawn_from..]
+                    .iter()
+                    .any(|drawn| overlaps(drawn, &area))
+                {
+                    self.draw_object_in_colors(object, &colors);
+                }
+            }
+        }
+    }
+    fn set_theme(&mut self, theme: Theme) {
+        self.theme = theme;
+        self.display.clear(theme.background).unwrap();
+    }
+}
+
+impl<'a, D: DrawTarget<Color = Rgb565>> Display<'a, D>
+where
+    D::Error: Debug,
+{
+    fn draw_objects_in_colors(
+        &mut self,
+        objects: &Vec<ScreenObject, CONTENT_CAPACITY>,
+        colors: ObjectColors,
+    ) {
+        for shape in objects.iter() {
+            self.draw_object_in_colors(shape, &colors);
+        }
+    }
+    fn draw_object_in_colors(&mut self, shape: &ScreenObject, colors: &ObjectColors) {
+        match shape {
+            ScreenObject::Paddle(rectangle, side) => {
+                let color = match side {
+                    LeftRightPosition::Left => colors.left_paddle_color,
+                    LeftRightPosition::Right => colors.right_paddle_color,
+                    LeftRightPosition::Top => colors.top_paddle_color,
+                    LeftRightPosition::Bottom => colors.bottom_paddle_color,
+                };
+                rectangle
+                    .into_styled(PrimitiveStyle::with_fill(color))
+                    .draw(self.display)
+                    .unwrap();
+            }
+            ScreenObject::Circle(circle) => {
+                circle
+                    .into_styled(PrimitiveStyle::with_fill(colors.ball_color))
+                    .draw(self.display)
+                    .unwrap();
+            }
+            ScreenObject::Text(text) => {
+                as_text(text, colors.text_color).draw(self.display).unwrap();
+            }
+            ScreenObject::CourtMarking(line) => {
+                line.into_styled(PrimitiveStyle::with_fill(colors.net_color))
+                    .draw(self.display)
+                    .unwrap();
+            }
+        }
+    }
+    fn get_object_colors(&self) -> ObjectColors {
+        ObjectColors {
+            left_paddle_color: self.theme.left_paddle,
+            right_paddle_color: self.theme.right_paddle,
+            top_paddle_color: self.theme.top_paddle,
+            bottom_paddle_color: self.theme.bottom_paddle,
+            ball_color: self.theme.ball,
+            text_color: self.theme.text,
+            net_color: self.theme.net,
+        }
+    }
+    fn get_clear_object_colors(&self) -> ObjectColors {
+        ObjectColors {
+            left_paddle_color: self.get_background_color(),
+            right_paddle_color: self.get_background_color(),
+            top_paddle_color: self.get_background_color(),
+            bottom_paddle_color: self.get_background_color(),
+            ball_color: self.get_background_color(),
+            text_color: self.get_background_color(),
+            net_color: self.get_background_color(),
+        }
+    }
+    fn get_background_color(&self) -> Rgb565 {
+        self.theme.background
+    }
+}
+
+fn as_text(text: &ScreenText, color: Rgb565) -> Text<'_, MonoTextStyle<'static, Rgb565>> {
+    let text_style = TextStyleBuilder::new()
+        .alignment(text.alignment)
+        .baseline(Baseline::Middle)
+        .build();
+    Text::with_text_style(
+        &text.content,
+        text.position,
+        MonoTextStyle::new(&FONT_6X10, color),
+        text_style,
+    )
+}
+
+/// The pixels an object may draw on.
+fn covered_area(object: &ScreenObject) -> Rectangle {
+    match object {
+        ScreenObject::Paddle(rectangle, _) | ScreenObject::CourtMarking(rectangle) => *rectangle,
+        ScreenObject::Circle(circle) => circle.bounding_box(),
+        ScreenObject::Text(text) => as_text(text, Rgb565::BLACK).bounding_box(),
+    }
+}
+
+fn is_shape(object: &ScreenObject) -> bool {
+    !matches!(object, ScreenObject::Text(_))
+}
+
+/// Changed since `previous`, or partly wiped out when something else was erased.
+fn needs_redraw(
+    previous: &Vec<ScreenObject, CONTENT_CAPACITY>,
+    index: usize,
+    object: &ScreenObject,
+    erased_areas: &[Rectangle],
+) -> bool {
+    let area = covered_area(object);
+    previous.get(index) != Some(object) || erased_areas.iter().any(|erased| overlaps(erased, &area))
+}
+
+fn overlaps(first: &Rectangle, second: &Rectangle) -> bool {
+    !first.intersection(second).is_zero_sized()
+}
+
+/// The parts of `old` outside `new`, as at most four rectangles: full width strips above and
+/// below `new` and the pieces left and right of it.
+fn uncovered_parts(old: &Rectangle, new: &Rectangle) -> Vec<Rectangle, 4> {
+    let mut parts: Vec<Rectangle, 4> = Vec::new();
+    let covered = old.intersection(new);
+    if covered.is_zero_sized() {
+        if !old.is_zero_sized() {
+            parts.push(*old).unwrap();
+        }
+        return parts;
+    }
+    let (old_left, old_top) = (old.top_left.x, old.top_left.y);
+    let old_right = old_left + old.size.width as i32;
+    let old_bottom = old_top + old.size.height as i32;
+    let (left, top) = (covered.top_left.x, covered.top_left.y);
+    let right = left + covered.size.width as i32;
+    let bottom = top + covered.size.height as i32;
+
+    for (x_start, y_start, x_end, y_end) in [
+        (old_left, old_top, old_right, top),
+        (old_left, bottom, old_right, old_bottom),
+        (old_left, top, left, bottom),
+        (right, top, old_right, bottom),
+    ] {
+        if x_end > x_start && y_end > y_start {
+            let part = Rectangle::new(
+                Point::new(x_start, y_start),
+                Size::new((x_end - x_start) as u32, (y_end - y_start) as u32),
+            );
+            parts.push(part).unwrap();
+        }
+    }
+    parts
+}
+
+#[cfg(test)]
+mod tests {
+    use embedded_graphics::mock_display::MockDisplay;
+    use embedded_graphics::primitives::Circle;
+    use embedded_graphics::text::Alignment;
+
+    use super::*;
+
+    fn rectangle(x: i32, y: i32, width: u32, height: u32) -> Rectangle {
+        Rectangle::new(Point::new(x, y), Size::new(width, height))
+    }
+
+    #[test]
+    fn uncovered_parts_of_moved_rectangle() {
+        let old = rectangle(0, 10, 6, 20);
+        assert_eq!(
+            uncovered_parts(&old, &rectangle(0, 13, 6, 20)).as_slice(),
+            &[rectangle(0, 10, 6, 3)]
+        );
+        assert_eq!(
+            uncovered_parts(&old, &rectangle(2, 8, 6, 20)).as_slice(),
+            &[rectangle(0, 28, 6, 2), rectangle(0, 10, 2, 18)]
+        );
+        assert!(uncovered_parts(&old, &old).is_empty());
+    }
+
+    #[test]
+    fn whole_rectangle_is_uncovered_when_moved_away() {
+        let old = rectangle(0, 10, 6, 20);
+        assert_eq!(
+            uncovered_parts(&old, &rectangle(20, 10, 6, 20)).as_slice(),
+            &[old]
+        );
+    }
+
+    fn content(paddle_y: i32, ball: Point, score: &str) -> Vec<ScreenObject, CONTENT_CAPACITY> {
+        let mut objects: Vec<ScreenObject, CONTENT_CAPACITY> = Vec::new();
+        objects
+            .push(ScreenObject::CourtMarking(rectangle(30, 40, 2, 20)))
+            .unwrap();
+        objects
+            .push(ScreenObject::Text(ScreenText::new(
+                Point::new(40, 6),
+                score,
+                Alignment::Center,
+            )))
+            .unwrap();
+        objects
+            .push(ScreenObject::Paddle(
+                rectangle(0, paddle_y, 6, 20),
+                LeftRightPosition::Left,
+            ))
+            .unwrap();
+        objects
+            .push(ScreenObject::Circle(Circle::with_center(ball, 7)))
+            .unwrap();
+        objects
+    }
+
+    fn screen() -> MockDisplay<Rgb565> {
+        let mut screen = MockDisplay::new();
+        screen.set_allow_overdraw(true);
+        screen.clear(Rgb565::BLACK).unwrap();
+        screen
+    }
+
+    #[test]
+    fn redraw_gives_same_picture_as_drawing_everything() {
+        let previous = content(20, Point::new(12, 30), "0");
+        for current in [
+            content(23, Point::new(14, 32), "0"),
+            content(20, Point::new(30, 50), "1"),
+            content(40, Point::new(8, 33), "0"),
+            // Passing over the court marking.
+            content(20, Point::new(28, 45), "0"),
+        ] {
+            let mut expected = screen();
+            Display {
+                display: &mut expected,
+                theme: Theme::CLASSIC,
+            }
+            .draw(&current);
+
+            let mut redrawn = screen();
+            let mut display = Display {
+                display: &mut redrawn,
+                theme: Theme::CLASSIC,
+            };
+            display.draw(&previous);
+            display.redraw(&previous, &current);
+
+            redrawn.assert_eq(&expected);
+        }
+    }
+
+    #[test]
+    fn redraw_handles_full_content_shrinking() {
+        // Every object leaves four uncovered parts behind.
+        let grid = |size: u32, offset: i32| -> Vec<ScreenObject, CONTENT_CAPACITY> {
+            (0..CONTENT_CAPACITY as i32)
+                .map(|index| {
+                    let (x, y) = (index % 8 * 8 + offset, index / 8 * 8 + offset);
+                    ScreenObject::Paddle(rectangle(x, y, size, size), LeftRightPosition::Left)
+                })
+                .collect()
+        };
+        let (previous, current) = (grid(8, 0), grid(2, 3));
+        let mut expected = screen();
+        Display {
+            display: &mut expected,
+            theme: Theme::CLASSIC,
+        }
+        .draw(&current);
+
+        let mut redrawn = screen();
+        let mut display = Display {
+            display: &mut redrawn,
+            theme: Theme::CLASSIC,
+        };
+        display.draw(&previous);
+        display.redraw(&previous, &current);
+
+        redrawn.assert_eq(&expected);
+    }
+
+    #[test]
+    fn paddles_get_their_own_colors() {
+        let mut screen = screen();
+        let mut display = Display {
+            display: &mut screen,
+            theme: Theme::COLOR_BLIND,
+        };
+        // The colour follows the owner, even for a paddle far from its wall.
+        let objects: Vec<ScreenObject, CONTENT_CAPACITY> = [
+            (rectangle(0, 0, 6, 20), LeftRightPosition::Left),
+            (rectangle(58, 0, 6, 20), LeftRightPosition::Right),
+            (rectangle(30, 0, 20, 6), LeftRightPosition::Top),
+            (rectangle(30, 58, 20, 6), LeftRightPosition::Bottom),
+            (rectangle(10, 30, 6, 20), LeftRightPosition::Right),
+        ]
+        .into_iter()
+        .map(|(area, side)| ScreenObject::Paddle(area, side))
+        .collect();
+        display.draw(&objects);
+
+        assert_eq!(
+            screen.get_pixel(Point::new(2, 2)),
+            Some(Theme::COLOR_BLIND.left_paddle)
+        );
+        assert_eq!(
+            screen.get_pixel(Point::new(60, 2)),
+            Some(Theme::COLOR_BLIND.right_paddle)
+        );
+        assert_eq!(
+            screen.get_pixel(Point::new(32, 2)),
+            Some(Theme::COLOR_BLIND.top_paddle)
+        );
+        assert_eq!(
+            screen.get_pixel(Point::new(32, 60)),
+            Some(Theme::COLOR_BLIND.bottom_paddle)
+        );
+        assert_eq!(
+            screen.get_pixel(Point::new(12, 40)),
+            Some(Theme::COLOR_BLIND.right_paddle)
+        );
+    }
+
+    #[test]
+    fn court_is_repaired_after_ball_passes() {
+        let previous = content(20, Point::new(31, 50), "0");
+        let current = content(20, Point::new(40, 50), "0");
+        let mut screen = screen();
+        let mut display = Display {
+            display: &mut screen,
+            theme: Theme::CLASSIC,
+        };
+        display.draw(&previous);
+        assert_eq!(
+            screen.get_pixel(Point::new(30, 50)),
+            Some(Theme::CLASSIC.ball)
+        );
+
+        let mut display = Display {
+            display: &mut screen,
+            theme: Theme::CLASSIC,
+        };
+        display.redraw(&previous, &current);
+        assert_eq!(
+            screen.get_pixel(Point::new(30, 50)),
+            Some(Theme::CLASSIC.net)
+        );
+    }
+
+    #[test]
+    fn new_theme_repaints_background() {
+        let mut screen = screen();
+        let mut display = Display {
+            display: &mut screen,
+            theme: Theme::CLASSIC,
+        };
+        display.set_theme(Theme::PAPER);
+        assert_eq!(display.theme, Theme::PAPER);
+        assert_eq!(screen.get_pixel(Point::new(30, 30)), Some(Rgb565::WHITE));
+    }
+}
@@ -0,0 +1,568 @@
+use embedded_hal::digital::v2::InputPin;
+
+use super::game_objects::Game;
+use super::physics::Fixed;
+
+/// Which wall a player defends. Only four-player games have paddles on the top and bottom walls.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum LeftRightPosition {
+    Left,
+    Right,
+    Top,
+    Bottom,
+}
+
+impl LeftRightPosition {
+    /// The side across the court.
+    pub fn opposite(&self) -> LeftRightPosition {
+        match self {
+            LeftRightPosition::Left => LeftRightPosition::Right,
+            LeftRightPosition::Right => LeftRightPosition::Left,
+            LeftRightPosition::Top => LeftRightPosition::Bottom,
+            LeftRightPosition::Bottom => LeftRightPosition::Top,
+        }
+    }
+}
+
+/// Which of a side's paddles. Only doubles games have a back paddle, between the front one and
+/// the wall, and each paddle has its own control.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PaddleRow {
+    Front,
+    Back,
+}
+
+impl PaddleRow {
+    /// Front first, in the order of `Game::get_paddles`.
+    pub const ALL: [PaddleRow; 2] = [PaddleRow::Front, PaddleRow::Back];
+}
+
+/// For the paddles on the top and bottom walls, which move sideways, up is left and down is right.
+#[derive(Debug, PartialEq)]
+pub enum InpuDirection {
+    Up,
+    Down,
+    Stay,
+}
+
+impl InpuDirection {
+    /// Maps a joystick deflection, 0-100% of its range, to a direction with a dead zone around
+    /// the centre.
+    pub fn from_percentage(input_percentage: u8) -> Result<InpuDirection, InputError> {
+        match input_percentage {
+            0..=39 => Ok(InpuDirection::Up),
+            40..=59 => Ok(InpuDirection::Stay),
+            60..=100 => Ok(InpuDirection::Down),
+            _ => Err(InputError::OutOfRange(input_percentage)),
+        }
+    }
+}
+
+/// Why a player's control couldn't be read.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum InputError {
+    /// A reading over 100%, check the connections.
+    OutOfRange(u8),
+    /// The control reads as if nothing is plugged in, see `ConnectionMonitor`.
+    Disconnected,
+}
+
+/// What a player does with their paddle.
+#[derive(Debug, PartialEq)]
+pub enum PaddleInput {
+    Direction(InpuDirection),
+    /// `magnitude` from 0 to 1 is the share of full speed to move at.
+    Analog {
+        direction: InpuDirection,
+        magnitude: Fixed,
+    },
+    /// Where the paddle should be, from 0 at the top of the court to 100 at the bottom, or from
+    /// left to right for the top and bottom paddles.
+    Position(u8),
+}
+
+impl PaddleInput {
+    /// Which way the input points, e.g. to move through a menu. A knob points up in the top
+    /// third of its travel and down in the bottom third.
+    pub fn direction(self) -> InpuDirection {
+        match self {
+            PaddleInput::Direction(direction) | PaddleInput::Analog { direction, .. } => direction,
+            PaddleInput::Position(0..=33) => InpuDirection::Up,
+            PaddleInput::Position(67..) => InpuDirection::Down,
+            PaddleInput::Position(_) => InpuDirection::Stay,
+        }
+    }
+}
+
+/// How a player's analog control moves their paddle.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum InputMode {
+    /// A stick that springs back to the middle, pushing it out of the dead zone moves the paddle
+    /// up or down at full speed. `dead_zone` works like `StickResponse::dead_zone`.
+    Joystick { dead_zone: u8 },
+    /// A stick where the paddle speed follows how far it is pushed.
+    AnalogStick(StickResponse),
+    /// A knob as in the original Pong, the paddle goes to the height it is turned to.
+    Knob(KnobSmoothing),
+}
+
+impl InputMode {
+    /// Reads a control at 0-100% of its range, whatever the mode a reading over 100% is an error.
+    pub fn paddle_input(&mut self, input_percentage: u8) -> Result<PaddleInput, InputError> {
+        if input_percentage > 100 {
+            return Err(InputError::OutOfRange(input_percentage));
+        }
+        match self {
+            InputMode::Joystick { dead_zone } => {
+                let response = StickResponse {
+                    dead_zone: *dead_zone,
+                    curve: ResponseCurve::Digital,
+                };
+                Ok(match response.paddle_input(input_percentage) {
+                    PaddleInput::Analog { direction, .. } => PaddleInput::Direction(direction),
+                    input => input,
+                })
+            }
+            InputMode::AnalogStick(response) => Ok(response.paddle_input(input_percentage)),
+            InputMode::Knob(smoothing) => {
+                Ok(PaddleInput::Position(smoothing.smooth(input_percentage)))
+            }
+        }
+    }
+}
+
+/// How the paddle speed grows as the stick is pushed out of its dead zone.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ResponseCurve {
+    /// Full speed as soon as the stick leaves the dead zone.
+    Digital,
+    /// Speed grows evenly with the deflection.
+    Linear,
+    /// Speed grows with the square of the deflection, for finer control close to the middle.
+    Quadratic,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct StickResponse {
+    /// Deflection either side of the middle, in percent of the stick's full range, that still
+    /// counts as centred.
+    pub dead_zone: u8,
+    pub curve: ResponseCurve,
+}
+
+impl StickResponse {
+    /// Reads a stick at 0-100% of its range.
+    pub fn paddle_input(&self, input_percentage: u8) -> PaddleInput {
+        let deflection = input_percentage.min(100) as i32 - 50;
+        let dead_zone = (self.dead_zone as i32).min(49);
+        let beyond_dead_zone = deflection.abs() - dead_zone;
+        if beyond_dead_zone <= 0 {
+            return PaddleInput::Direction(InpuDirection::Stay);
+        }
+        let share = Fixed::from_ratio(beyond_dead_zone, 50 - dead_zone);
+        PaddleInput::Analog {
+            direction: match deflection < 0 {
+                true => InpuDirection::Up,
+                false => InpuDirection::Down,
+            },
+            magnitude: match self.curve {
+                ResponseCurve::Digital => Fixed::ONE,
+                ResponseCurve::Linear => share,
+                ResponseCurve::Quadratic => share * share,
+            },
+        }
+    }
+}
+
+/// Exponential smoothing of knob readings. Keeps ADC noise from shaking the paddle, at the cost
+/// of following a quick turn a few frames late.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct KnobSmoothing {
+    /// Share of each new reading in the result, `Fixed::ONE` follows the knob without smoothing.
+    new_reading_weight: Fixed,
+    smoothed: Option<Fixed>,
+}
+
+impl KnobSmoothing {
+    pub const fn new(new_reading_weight: Fixed) -> KnobSmoothing {
+        KnobSmoothing {
+            new_reading_weight,
+            smoothed: None,
+        }
+    }
+
+    pub fn smooth(&mut self, input_percentage: u8) -> u8 {
+        let reading = Fixed::from_int(input_percentage as i32);
+        let smoothed = match self.smoothed {
+            Some(smoothed) => smoothed + (reading - smoothed) * self.new_reading_weight,
+            None => reading,
+        };
+        self.smoothed = Some(smoothed);
+        smoothed.round() as u8
+    }
+}
+
+pub trait UserInteraction {
+    fn get_input_direction(
+        &mut self,
+        user_position: &LeftRightPosition,
+    ) -> Result<InpuDirection, InputError>;
+    /// How to move the paddle. Controls that only know directions can leave this out.
+    fn get_paddle_input(
+        &mut self,
+        user_position: &LeftRightPosition,
+    ) -> Result<PaddleInput, InputError> {
+        self.get_input_direction(user_position)
+            .map(PaddleInput::Direction)
+    }
+    /// How to move the back paddle in a doubles game. Controls without one for the back player
+    /// move both paddles together.
+    fn get_back_paddle_input(
+        &mut self,
+        user_position: &LeftRightPosition,
+    ) -> Result<PaddleInput, InputError> {
+        self.get_paddle_input(user_position)
+    }
+    /// Called once per frame before the input is read, for inputs that react to the game itself.
+    fn observe_game(&mut self, _game: &Game) {}
+}
+
+/// Takes the right player's input from one source and everyone else's from another, e.g. to
+/// play against the computer.
+pub struct SplitInputs<L: UserInteraction, R: UserInteraction> {
+    pub left: L,
+    pub right: R,
+}
+
+impl<L: UserInteraction, R: UserInteraction> UserInteraction for SplitInputs<L, R> {
+    fn get_input_direction(
+        &mut self,
+        user_position: &LeftRightPosition,
+    ) -> Result<InpuDirection, InputError> {
+        match user_position {
+            LeftRightPosition::Right => self.right.get_input_direction(user_position),
+            _ => self.left.get_input_direction(user_position),
+        }
+    }
+    fn get_paddle_input(
+        &mut self,
+        user_position: &LeftRightPosition,
+    ) -> Result<PaddleInput, InputError> {
+        match user_position {
+            LeftRightPosition::Right => self.right.get_paddle_input(user_position),
+            _ => self.left.get_paddle_input(user_position),
+        }
+    }
+    fn get_back_paddle_input(
+        &mut self,
+        user_position: &LeftRightPosition,
+    ) -> Result<PaddleInput, InputError> {
+        match user_position {
+            LeftRightPosition::Right => self.right.get_back_paddle_input(user_position),
+            _ => self.left.get_back_paddle_input(user_position),
+        }
+    }
+    fn observe_game(&mut self, game: &Game) {
+        self.left.observe_game(game);
+        self.right.observe_game(game);
+    }
+}
+
+/// A push button, for serving, pausing and going through the menus.
+pub trait ButtonInput {
+    /// Whether the button has been pressed since the last call.
+    fn was_pressed(&mut self) -> bool;
+}
+
+/// A button on a GPIO pin, e.g. the Nucleo's user button on PC13. Contacts bounce for a few
+/// milliseconds when pressed or released, so a new level only counts once the pin has read it
+/// `stable_samples` times in a row.
+pub struct DebouncedButton<P: InputPin> {
+    pin: P,
+    /// The pin reads low while the button is held, as with PC13.
+    active_low: bool,
+    stable_samples: u8,
+    /// Samples in a row that disagree with `is_down`.
+    changed_samples: u8,
+    is_down: bool,
+    pressed: bool,
+}
+
+impl<P: InputPin> DebouncedButton<P> {
+    /// A button already held down doesn't count as pressed until it has been let go.
+    pub fn new(pin: P, active_low: bool, stable_samples: u8) -> DebouncedButton<P> {
+        let is_down = pin.is_high().is_ok_and(|is_high| is_high != active_low);
+        DebouncedButton {
+            pin,
+            active_low,
+            stable_samples,
+            changed_samples: 0,
+            is_down,
+            pressed: false,
+        }
+    }
+
+    pub fn is_down(&self) -> bool {
+        self.is_down
+    }
+
+    /// Reads the pin once. Call it regularly, e.g. every tick, the debounce time is
+    /// `stable_samples` times the time between calls.
+    pub fn sample(&mut self) {
+        // A pin that can't be read keeps its last level.
+        let is_down = match self.pin.is_high() {
+            Ok(is_high) => is_high != self.active_low,
+            Err(_) => self.is_down,
+        };
+        if is_down == self.is_down {
+            self.changed_samples = 0;
+            return;
+        }
+        self.changed_samples += 1;
+        if self.changed_samples >= self.stable_samples {
+            self.is_down = is_down;
+            self.changed_samples = 0;
+            self.pressed |= is_down;
+        }
+    }
+}
+
+impl<P: InputPin> ButtonInput for DebouncedButton<P> {
+    /// Takes a sample first, so calling only this is enough.
+    fn was_pressed(&mut self) -> bool {
+        self.sample();
+        let pressed = self.pressed;
+        self.pressed = false;
+        pressed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::cell::Cell;
+    use core::convert::Infallible;
+
+    use super::*;
+
+    /// A pin whose level the test sets.
+    struct MockPin<'a>(&'a Cell<bool>);
+
+    impl InputPin for MockPin<'_> {
+        type Error = Infallible;
+        fn is_high(&self) -> Result<bool, Infallible> {
+            Ok(self.0.get())
+        }
+        fn is_low(&self) -> Result<bool, Infallible> {
+            Ok(!self.0.get())
+        }
+    }
+
+    /// Feeds the pin levels one sample at a time, returns at which samples a press was seen.
+    fn presses(button: &mut DebouncedButton<MockPin>, level: &Cell<bool>, levels: &[bool]) -> u32 {
+        let mut presses = 0;
+        for (sample, is_high) in levels.iter().enumerate() {
+            level.set(*is_high);
+            if button.was_pressed() {
+                presses |= 1 << sample;
+            }
+        }
+        presses
+    }
+
+    #[test]
+    fn press_counts_once_level_is_stable() {
+        let level = Cell::new(true);
+        let mut button = DebouncedButton::new(MockPin(&level), true, 3);
+        let held = [true, false, false, false, false, false, true, true, true];
+        assert_eq!(presses(&mut button, &level, &held), 1 << 3);
+    }
+
+    #[test]
+    fn bounces_are_ignored() {
+        let level = Cell::new(true);
+        let mut button = DebouncedButton::new(MockPin(&level), true, 3);
+        let bouncing = [false, true, false, false, true, false, false, false, true];
+        assert_eq!(presses(&mut button, &level, &bouncing), 1 << 7);
+        // Bouncing on release doesn't count as another press.
+        let releasing = [true, false, true, true, true, false, false];
+        assert_eq!(presses(&mut button, &level, &releasing), 0);
+    }
+
+    #[test]
+    fn active_high_button() {
+        let level = Cell::new(false);
+        let mut button = DebouncedButton::new(MockPin(&level), false, 1);
+        assert_eq!(presses(&mut button, &level, &[false, true, true]), 1 << 1);
+    }
+
+    #[test]
+    fn held_at_start_is_not_a_press() {
+        let level = Cell::new(false);
+        let mut button = DebouncedButton::new(MockPin(&level), true, 1);
+        assert!(button.is_down());
+        assert_eq!(presses(&mut button, &level, &[false, true, false]), 1 << 2);
+    }
+
+    #[test]
+    fn press_is_reported_once() {
+        let level = Cell::new(true);
+        let mut button = DebouncedButton::new(MockPin(&level), true, 2);
+        level.set(false);
+        button.sample();
+        button.sample();
+        assert!(button.was_pressed());
+        assert!(!button.was_pressed());
+    }
+
+    #[test]
+    fn low_percentage_moves_up() {
+        assert_eq!(InpuDirection::from_percentage(0), Ok(InpuDirection::Up));
+        assert_eq!(InpuDirection::from_percentage(39), Ok(InpuDirection::Up));
+    }
+
+    #[test]
+    fn centre_percentage_stays() {
+        assert_eq!(InpuDirection::from_percentage(40), Ok(InpuDirection::Stay));
+        assert_eq!(InpuDirection::from_percentage(59), Ok(InpuDirection::Stay));
+    }
+
+    #[test]
+    fn high_percentage_moves_down() {
+        assert_eq!(InpuDirection::from_percentage(60), Ok(InpuDirection::Down));
+        assert_eq!(InpuDirection::from_percentage(100), Ok(InpuDirection::Down));
+    }
+
+    #[test]
+    fn percentage_above_100_is_rejected() {
+        assert_eq!(
+            InpuDirection::from_percentage(101),
+            Err(InputError::OutOfRange(101))
+        );
+    }
+
+    #[test]
+    fn joystick_dead_zone_is_configurable() {
+        let mut narrow = InputMode::Joystick { dead_zone: 2 };
+        let mut wide = InputMode::Joystick { dead_zone: 30 };
+        assert_eq!(
+            narrow.paddle_input(55),
+            Ok(PaddleInput::Direction(InpuDirection::Down))
+        );
+        assert_eq!(
+            wide.paddle_input(55),
+            Ok(PaddleInput::Direction(InpuDirection::Stay))
+        );
+        assert_eq!(
+            wide.paddle_input(15),
+            Ok(PaddleInput::Direction(InpuDirection::Up))
+        );
+        assert_eq!(wide.paddle_input(101), Err(InputError::OutOfRange(101)));
+    }
+
+    #[test]
+    fn every_mode_rejects_percentage_above_100() {
+        let modes = [
+            InputMode::Joystick { dead_zone: 10 },
+            InputMode::AnalogStick(StickResponse {
+                dead_zone: 10,
+                curve: ResponseCurve::Linear,
+            }),
+            InputMode::Knob(KnobSmoothing::new(Fixed::ONE)),
+        ];
+        for mut mode in modes {
+            assert_eq!(mode.paddle_input(101), Err(InputError::OutOfRange(101)));
+        }
+    }
+
+    #[test]
+    fn every_input_points_somewhere() {
+        let analog = PaddleInput::Analog {
+            direction: InpuDirection::Down,
+            magnitude: Fixed::from_ratio(1, 2),
+        };
+        assert_eq!(analog.direction(), InpuDirection::Down);
+        assert_eq!(PaddleInput::Position(10).direction(), InpuDirection::Up);
+        assert_eq!(PaddleInput::Position(50).direction(), InpuDirection::Stay);
+        assert_eq!(PaddleInput::Position(90).direction(), InpuDirection::Down);
+    }
+
+    #[test]
+    fn joystick_gives_directions_and_knob_positions() {
+        assert_eq!(
+            InputMode::Joystick { dead_zone: 10 }.paddle_input(10),
+            Ok(PaddleInput::Direction(InpuDirection::Up))
+        );
+        let mut knob = InputMode::Knob(KnobSmoothing::new(Fixed::ONE));
+        assert_eq!(knob.paddle_input(10), Ok(PaddleInput::Position(10)));
+        assert_eq!(knob.paddle_input(50), Ok(PaddleInput::Position(50)));
+    }
+
+    fn magnitude(input: PaddleInput) -> Fixed {
+        match input {
+            PaddleInput::Analog { magnitude, .. } => magnitude,
+            PaddleInput::Direction(InpuDirection::Stay) => Fixed::ZERO,
+            other => panic!("Expected analog input, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn stick_response_follows_curve() {
+        let response = |curve| StickResponse {
+            dead_zone: 10,
+            curve,
+        };
+        let linear = response(ResponseCurve::Linear);
+        assert_eq!(
+            linear.paddle_input(0),
+            PaddleInput::Analog {
+                direction: InpuDirection::Up,
+                magnitude: Fixed::ONE
+            }
+        );
+        assert_eq!(magnitude(linear.paddle_input(80)), Fixed::from_ratio(1, 2));
+        assert_eq!(
+            magnitude(response(ResponseCurve::Quadratic).paddle_input(80)),
+            Fixed::from_ratio(1, 4)
+        );
+        assert_eq!(
+            magnitude(response(ResponseCurve::Digital).paddle_input(61)),
+            Fixed::ONE
+        );
+    }
+
+    #[test]
+    fn stick_dead_zone_is_configurable() {
+        let narrow = StickResponse {
+            dead_zone: 2,
+            curve: ResponseCurve::Linear,
+        };
+        let wide = StickResponse {
+            dead_zone: 30,
+            ..narrow
+        };
+        assert_eq!(magnitude(narrow.paddle_input(45)), Fixed::from_ratio(3, 48));
+        assert_eq!(
+            wide.paddle_input(75),
+            PaddleInput::Direction(InpuDirection::Stay)
+        );
+        assert_eq!(
+            wide.paddle_input(90),
+            PaddleInput::Analog {
+                direction: InpuDirection::Down,
+                magnitude: Fixed::from_ratio(1, 2)
+            }
+        );
+    }
+
+    #[test]
+    fn knob_readings_are_smoothed() {
+        let mut smoothing = KnobSmoothing::new(Fixed::from_ratio(1, 4));
+        assert_eq!(smoothing.smooth(40), 40);
+        // Noise of a few percent hardly moves it.
+        assert_eq!(smoothing.smooth(44), 41);
+        assert_eq!(smoothing.smooth(38), 40);
+        // A turn is followed within a few readings.
+        let readings: [u8; 4] = core::array::from_fn(|_| smoothing.smooth(80));
+        assert_eq!(readings, [50, 58, 63, 67]);
+    }
+}
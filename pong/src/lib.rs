@@ -0,0 +1,12 @@
+#![no_std]
+
+pub mod calibration;
+pub mod computer_player;
+pub mod connection;
+pub mod filter;
+pub mod game_objects;
+pub mod graphics;
+pub mod input;
+pub mod physics;
+pub mod random;
+pub mod session;
@@ -0,0 +1,553 @@
+use core::ops::{Add, AddAssign, Div, Mul, Neg, Sub, SubAssign};
+
+use embedded_graphics::{prelude::Point, primitives::Rectangle};
+use heapless::Vec;
+
+use super::game_objects::paddle::Paddle;
+use super::game_objects::PADDLES_PER_SIDE;
+use super::input::LeftRightPosition;
+
+/// Q16.16 fixed point number, for positions and speeds finer than a pixel without floating point
+/// maths.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Fixed(i32);
+
+impl Fixed {
+    const FRACTIONAL_BITS: u32 = 16;
+    pub const ZERO: Fixed = Fixed(0);
+    pub const ONE: Fixed = Fixed(1 << Fixed::FRACTIONAL_BITS);
+
+    pub const fn from_int(value: i32) -> Fixed {
+        Fixed(value << Fixed::FRACTIONAL_BITS)
+    }
+    pub const fn from_ratio(numerator: i32, denominator: i32) -> Fixed {
+        Fixed((((numerator as i64) << Fixed::FRACTIONAL_BITS) / denominator as i64) as i32)
+    }
+    /// Nearest whole number, halves are rounded up.
+    pub const fn round(self) -> i32 {
+        (self.0 + (1 << (Fixed::FRACTIONAL_BITS - 1))) >> Fixed::FRACTIONAL_BITS
+    }
+    /// Smallest whole number not less than `self`.
+    pub const fn ceil(self) -> i32 {
+        (self.0 + (1 << Fixed::FRACTIONAL_BITS) - 1) >> Fixed::FRACTIONAL_BITS
+    }
+    pub const fn abs(self) -> Fixed {
+        Fixed(self.0.abs())
+    }
+    pub const fn signum(self) -> i32 {
+        self.0.signum()
+    }
+    /// `self * self`, widened so that it can't overflow for anything on screen.
+    pub const fn squared_raw(self) -> i64 {
+        self.0 as i64 * self.0 as i64
+    }
+}
+
+impl Add for Fixed {
+    type Output = Fixed;
+    fn add(self, other: Fixed) -> Fixed {
+        Fixed(self.0 + other.0)
+    }
+}
+
+impl AddAssign for Fixed {
+    fn add_assign(&mut self, other: Fixed) {
+        self.0 += other.0;
+    }
+}
+
+impl Sub for Fixed {
+    type Output = Fixed;
+    fn sub(self, other: Fixed) -> Fixed {
+        Fixed(self.0 - other.0)
+    }
+}
+
+impl SubAssign for Fixed {
+    fn sub_assign(&mut self, other: Fixed) {
+        self.0 -= other.0;
+    }
+}
+
+impl Neg for Fixed {
+    type Output = Fixed;
+    fn neg(self) -> Fixed {
+        Fixed(-self.0)
+    }
+}
+
+impl Mul for Fixed {
+    type Output = Fixed;
+    fn mul(self, other: Fixed) -> Fixed {
+        Fixed(((self.0 as i64 * other.0 as i64) >> Fixed::FRACTIONAL_BITS) as i32)
+    }
+}
+
+impl Mul<i32> for Fixed {
+    type Output = Fixed;
+    fn mul(self, other: i32) -> Fixed {
+        Fixed(self.0 * other)
+    }
+}
+
+impl Div for Fixed {
+    type Output = Fixed;
+    fn div(self, other: Fixed) -> Fixed {
+        Fixed((((self.0 as i64) << Fixed::FRACTIONAL_BITS) / other.0 as i64) as i32)
+    }
+}
+
+impl Div<i32> for Fixed {
+    type Output = Fixed;
+    fn div(self, other: i32) -> Fixed {
+        Fixed(self.0 / other)
+    }
+}
+
+/// A position with sub-pixel precision. Rounded to a `Point` when drawn.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct FixedPoint {
+    pub x: Fixed,
+    pub y: Fixed,
+}
+
+impl FixedPoint {
+    pub fn round(&self) -> Point {
+        Point {
+            x: self.x.round(),
+            y: self.y.round(),
+        }
+    }
+    /// Mirrored along the diagonal, x and y swap places.
+    pub fn transposed(&self) -> FixedPoint {
+        FixedPoint {
+            x: self.y,
+            y: self.x,
+        }
+    }
+}
+
+impl From<Point> for FixedPoint {
+    fn from(point: Point) -> FixedPoint {
+        FixedPoint {
+            x: Fixed::from_int(point.x),
+            y: Fixed::from_int(point.y),
+        }
+    }
+}
+
+impl Add for FixedPoint {
+    type Output = FixedPoint;
+    fn add(self, other: FixedPoint) -> FixedPoint {
+        FixedPoint {
+            x: self.x + other.x,
+            y: self.y + other.y,
+        }
+    }
+}
+
+impl Sub for FixedPoint {
+    type Output = FixedPoint;
+    fn sub(self, other: FixedPoint) -> FixedPoint {
+        FixedPoint {
+            x: self.x - other.x,
+            y: self.y - other.y,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Velocity {
+    // Direction of movement from the balls frame of reference, in pixels per time step.
+    pub vx: Fixed,
+    pub vy: Fixed,
+}
+
+impl Velocity {
+    /// Whole pixels per time step.
+    pub const fn from_pixels(vx: i32, vy: i32) -> Velocity {
+        Velocity {
+            vx: Fixed::from_int(vx),
+            vy: Fixed::from_int(vy),
+        }
+    }
+    pub fn transposed(&self) -> Velocity {
+        Velocity {
+            vx: self.vy,
+            vy: self.vx,
+        }
+    }
+}
+
+#[derive(Copy, Clone, Default)]
+pub struct TimeTick {
+    /// Pixels a paddle moves per tick.
+    pub max_paddle_movement: u32,
+    /// Fastest the ball may move, in pixels per tick.
+    pub max_ball_movement: u32,
+    /// Number of ticks the next update covers, see `FixedTimestep`.
+    pub time_step: u32,
+}
+
+/// Turns real elapsed time into whole simulation ticks of fixed length, so the game runs at the
+/// same speed however long drawing takes. Time that doesn't add up to a full tick is carried over
+/// to the next frame.
+#[derive(Copy, Clone, Debug)]
+pub struct FixedTimestep {
+    tick_length_us: u32,
+    max_ticks_per_frame: u32,
+    unsimulated_us: u32,
+}
+
+impl FixedTimestep {
+    pub fn new(tick_length_us: u32, max_ticks_per_frame: u32) -> FixedTimestep {
+        FixedTimestep {
+            tick_length_us: tick_length_us.max(1),
+            max_ticks_per_frame,
+            unsimulated_us: 0,
+        }
+    }
+
+    /// Adds `elapsed_us` of real time and returns the number of ticks to simulate. Anything
+    /// beyond `max_ticks_per_frame` is dropped, so a long stall doesn't make the ball jump.
+    pub fn advance(&mut self, elapsed_us: u32) -> u32 {
+        self.unsimulated_us = self.unsimulated_us.saturating_add(elapsed_us);
+        let ticks = self.unsimulated_us / self.tick_length_us;
+        match ticks > self.max_ticks_per_frame {
+            true => {
+                self.unsimulated_us = 0;
+                self.max_ticks_per_frame
+            }
+            false => {
+                self.unsimulated_us -= ticks * self.tick_length_us;
+                ticks
+            }
+        }
+    }
+}
+
+/// How the ball leaves a paddle. The default bounces it straight back like a mirror.
+#[derive(Copy, Clone, Default, Debug)]
+pub struct PaddleBounce {
+    /// Vertical speed of a ball hitting the very end of the paddle. The further from the middle of
+    /// the paddle the ball hits, the steeper it leaves. 0 keeps the incoming angle.
+    pub max_vertical_speed: u32,
+    /// Vertical speed added in the direction the paddle was moving when the ball hit it.
+    pub paddle_movement_speed: u32,
+}
+
+/// How the ball speeds up during a rally. Each increase adds one pixel per tick to the
+/// horizontal speed, up to what `TimeTick::max_ball_movement` allows.
+#[derive(Copy, Clone, Default, Debug)]
+pub enum SpeedUp {
+    /// The ball keeps its serve speed.
+    #[default]
+    Constant,
+    /// The speed increases every `hits_per_increase` paddle hits.
+    Linear { hits_per_increase: u32 },
+    /// The first increase comes after `first_increase_after` paddle hits, and every following
+    /// increase takes twice as many hits as the one before.
+    Slowing { first_increase_after: u32 },
+}
+
+impl SpeedUp {
+    /// Number of speed increases after `hits` paddle hits.
+    pub fn increases_after(&self, hits: u32) -> u32 {
+        match *self {
+            SpeedUp::Constant => 0,
+            SpeedUp::Linear { hits_per_increase } => hits / hits_per_increase.max(1),
+            SpeedUp::Slowing {
+                first_increase_after,
+            } => {
+                // Increase n comes after first * (2^n - 1) hits.
+                let hits_in_units = hits / first_increase_after.max(1);
+                (hits_in_units + 1).ilog2()
+            }
+        }
+    }
+}
+
+/// When a ball of `radius` moving from `start` by `movement` first touches `area`, as the share
+/// of the movement from 0 to 1. Worked out directly instead of by following the ball, so that no
+/// ball is too fast to be caught. A ball that already overlaps `area` hits it at 0, unless it is
+/// only touching it and moves away. `None` if it misses.
+pub fn time_of_impact(
+    start: FixedPoint,
+    movement: FixedPoint,
+    radius: Fixed,
+    area: &Rectangle,
+) -> Option<Fixed> {
+    let bottom_right = area.bottom_right().unwrap_or(area.top_left);
+    let raw = |value: i32| Fixed::from_int(value).0 as i128;
+    let (left, top) = (raw(area.top_left.x), raw(area.top_left.y));
+    let (right, bottom) = (raw(bottom_right.x), raw(bottom_right.y));
+    // Wide enough that nothing overflows, however far the ball goes.
+    let start = (start.x.0 as i128, start.y.0 as i128);
+    let movement = (movement.x.0 as i128, movement.y.0 as i128);
+    let radius = radius.0 as i128;
+    if movement == (0, 0) {
+        return None;
+    }
+
+    let to_closest = (
+        start.0.clamp(left, right) - start.0,
+        start.1.clamp(top, bottom) - start.1,
+    );
+    if to_closest.0.pow(2) + to_closest.1.pow(2) <= radius.pow(2) {
+        let is_inside = to_closest == (0, 0);
+        let moves_further_in = to_closest.0 * movement.0 + to_closest.1 * movement.1 > 0;
+        return match is_inside || moves_further_in {
+            true => Some(Fixed::ZERO),
+            false => None,
+        };
+    }
+    // The ball touches `area` once its centre is in `area` grown by the radius: `area` stretched
+    // sideways, `area` stretched up and down, or a circle around one of the corners.
+    let stretched = [
+        time_to_enter_box(
+            start,
+            movement,
+            (left - radius, top),
+            (right + radius, bottom),
+        ),
+        time_to_enter_box(
+            start,
+            movement,
+            (left, top - radius),
+            (right, bottom + radius),
+        ),
+    ];
+    let corners = [(left, top), (right, top), (left, bottom), (right, bottom)]
+        .map(|corner| time_to_reach_circle(start, movement, corner, radius));
+    stretched
+        .into_iter()
+        .chain(corners)
+        .flatten()
+        .min()
+        .map(|time| Fixed(time as i32))
+}
+
+/// Raw share of `movement` after which `start` is inside the box from `low` to `high`, if it
+/// gets there. `start` is outside.
+fn time_to_enter_box(
+    start: (i128, i128),
+    movement: (i128, i128),
+    low: (i128, i128),
+    high: (i128, i128),
+) -> Option<i128> {
+    let one = Fixed::ONE.0 as i128;
+    let (mut enter, mut leave) = (0, one);
+    for (start, movement, low, high) in [
+        (start.0, movement.0, low.0, high.0),
+        (start.1, movement.1, low.1, high.1),
+    ] {
+        if movement == 0 {
+            if start < low || start > high {
+                return None;
+            }
+            continue;
+        }
+        let (to_low, to_high) = (
+            (low - start) * one / movement,
+            (high - start) * one / movement,
+        );
+        enter = enter.max(to_low.min(to_high));
+        leave = leave.min(to_low.max(to_high));
+    }
+    match enter <= leave {
+        true => Some(enter),
+        false => None,
+    }
+}
+
+/// Raw share of `movement` after which `start` is `radius` from `center`, if it gets there.
+/// `start` is further away.
+fn time_to_reach_circle(
+    start: (i128, i128),
+    movement: (i128, i128),
+    center: (i128, i128),
+    radius: i128,
+) -> Option<i128> {
+    // |from_center + time * movement| = radius, solved for the smaller time.
+    let from_center = (start.0 - center.0, start.1 - center.1);
+    let a = movement.0.pow(2) + movement.1.pow(2);
+    let half_b = from_center.0 * movement.0 + from_center.1 * movement.1;
+    let c = from_center.0.pow(2) + from_center.1.pow(2) - radius.pow(2);
+    let discriminant = half_b.pow(2) - a * c;
+    if a == 0 || half_b >= 0 || discriminant < 0 {
+        return None;
+    }
+    let one = Fixed::ONE.0 as i128;
+    let time = (-half_b - discriminant.isqrt()) * one / a;
+    match time <= one {
+        true => Some(time),
+        false => None,
+    }
+}
+
+/// What a moving object can bounce off.
+pub struct Obstacles<'a> {
+    /// The area between the top and bottom walls, `None` when they are goals.
+    pub walls: Option<Rectangle>,
+    pub paddles: &'a [(LeftRightPosition, Vec<Paddle, PADDLES_PER_SIDE>)],
+}
+
+/// A bounce partway through a movement.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Bounce {
+    /// Share of the movement made before the bounce.
+    pub time: Fixed,
+    /// Whose paddle was hit, `None` for a wall.
+    pub paddle: Option<LeftRightPosition>,
+}
+
+pub trait BouncableObject {
+    /// Moves the object by `movement` up to the first wall or paddle in its way and bounces off
+    /// it. Only the paddles on the sides it is heading for count, both of them in doubles. `None`
+    /// if nothing was in the way and the whole movement was made.
+    fn bounce_off_first_obstacle(
+        &mut self,
+        movement: &FixedPoint,
+        obstacles: &Obstacles,
+        paddle_bounce: &PaddleBounce,
+    ) -> Option<Bounce>;
+}
+
+pub trait MovingObject {
+    fn get_relative_movement(&self, time: &TimeTick) -> FixedPoint;
+    fn get_velocity(&self) -> Velocity;
+}
+
+#[cfg(test)]
+mod tests {
+    use embedded_graphics::prelude::Size;
+
+    use super::*;
+
+    #[test]
+    fn fixed_rounds_to_nearest_pixel() {
+        assert_eq!(Fixed::from_ratio(5, 4).round(), 1);
+        assert_eq!(Fixed::from_ratio(3, 2).round(), 2);
+        assert_eq!(Fixed::from_ratio(-3, 2).round(), -1);
+        assert_eq!(Fixed::from_ratio(-7, 4).round(), -2);
+        assert_eq!(Fixed::from_ratio(5, 4).ceil(), 2);
+        assert_eq!(Fixed::from_int(-2).ceil(), -2);
+    }
+
+    #[test]
+    fn fixed_arithmetic() {
+        let half = Fixed::from_ratio(1, 2);
+        assert_eq!(half + half, Fixed::ONE);
+        assert_eq!(Fixed::from_int(3) * half, Fixed::from_ratio(3, 2));
+        assert_eq!(
+            Fixed::from_int(3) / Fixed::from_int(4),
+            Fixed::from_ratio(3, 4)
+        );
+        assert_eq!(-half * 4, Fixed::from_int(-2));
+        assert_eq!(Fixed::from_int(-5) / 2, Fixed::from_ratio(-5, 2));
+    }
+
+    #[test]
+    fn timestep_carries_over_partial_ticks() {
+        let mut timestep = FixedTimestep::new(10_000, 4);
+        assert_eq!(timestep.advance(15_000), 1);
+        assert_eq!(timestep.advance(4_000), 0);
+        assert_eq!(timestep.advance(1_000), 1);
+        assert_eq!(timestep.advance(30_000), 3);
+    }
+
+    #[test]
+    fn timestep_drops_time_after_stall() {
+        let mut timestep = FixedTimestep::new(10_000, 4);
+        assert_eq!(timestep.advance(3_000_000), 4);
+        assert_eq!(timestep.advance(5_000), 0);
+        assert_eq!(timestep.advance(5_000), 1);
+    }
+
+    #[test]
+    fn constant_never_speeds_up() {
+        assert_eq!(SpeedUp::Constant.increases_after(100), 0);
+    }
+
+    #[test]
+    fn linear_speeds_up_evenly() {
+        let speed_up = SpeedUp::Linear {
+            hits_per_increase: 3,
+        };
+        assert_eq!(speed_up.increases_after(2), 0);
+        assert_eq!(speed_up.increases_after(3), 1);
+        assert_eq!(speed_up.increases_after(9), 3);
+    }
+
+    #[test]
+    fn slowing_needs_twice_as_many_hits_each_time() {
+        let speed_up = SpeedUp::Slowing {
+            first_increase_after: 2,
+        };
+        assert_eq!(speed_up.increases_after(1), 0);
+        assert_eq!(speed_up.increases_after(2), 1);
+        assert_eq!(speed_up.increases_after(5), 1);
+        assert_eq!(speed_up.increases_after(6), 2);
+        assert_eq!(speed_up.increases_after(14), 3);
+    }
+
+    fn point(x: i32, y: i32) -> FixedPoint {
+        FixedPoint::from(Point { x, y })
+    }
+
+    fn paddle_area() -> Rectangle {
+        Rectangle::new(Point { x: 0, y: 0 }, Size::new(6, 40))
+    }
+
+    #[test]
+    fn ball_touches_side_of_area() {
+        let time = time_of_impact(
+            point(14, 20),
+            point(-12, 0),
+            Fixed::from_int(3),
+            &paddle_area(),
+        );
+        assert_eq!(time, Some(Fixed::from_ratio(1, 2)));
+    }
+
+    #[test]
+    fn ball_touches_corner_of_area() {
+        // Five pixels from the corner at (5, 39) once the centre is at (8, 43).
+        let time = time_of_impact(
+            point(20, 43),
+            point(-24, 0),
+            Fixed::from_int(5),
+            &paddle_area(),
+        );
+        assert_eq!(time, Some(Fixed::from_ratio(1, 2)));
+    }
+
+    #[test]
+    fn ball_misses_area() {
+        let time = time_of_impact(
+            point(20, 60),
+            point(-40, 0),
+            Fixed::from_int(3),
+            &paddle_area(),
+        );
+        assert_eq!(time, None);
+    }
+
+    #[test]
+    fn ball_touching_area_hits_it_only_when_moving_in() {
+        let radius = Fixed::from_int(3);
+        let time = time_of_impact(point(8, 20), point(-1, 0), radius, &paddle_area());
+        assert_eq!(time, Some(Fixed::ZERO));
+        let time = time_of_impact(point(8, 20), point(10, 0), radius, &paddle_area());
+        assert_eq!(time, None);
+    }
+
+    #[test]
+    fn ball_inside_area_hits_it_at_once() {
+        let radius = Fixed::from_int(3);
+        let time = time_of_impact(point(3, 20), point(10, 0), radius, &paddle_area());
+        assert_eq!(time, Some(Fixed::ZERO));
+        let time = time_of_impact(point(3, 20), point(0, 0), radius, &paddle_area());
+        assert_eq!(time, None);
+    }
+}
@@ -0,0 +1,88 @@
+/// Pseudo-random numbers for gameplay, e.g. serve angles and the computer's mistakes.
+pub trait Random {
+    fn next_u32(&mut self) -> u32;
+
+    /// Evenly spread from `-limit` to `limit`, both included.
+    fn next_between(&mut self, limit: u32) -> i64 {
+        let range = 2 * limit as u64 + 1;
+        (self.next_u32() as u64 % range) as i64 - limit as i64
+    }
+}
+
+/// xorshift32, small and fast. Plenty for a game, but easy to predict.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct XorShift32 {
+    state: u32,
+}
+
+impl XorShift32 {
+    pub const DEFAULT_SEED: u32 = 0x2545_f491;
+
+    /// The same seed always gives the same numbers. A zero seed would only ever give zeros, so
+    /// `DEFAULT_SEED` is used instead.
+    pub fn new(seed: u32) -> XorShift32 {
+        XorShift32 {
+            state: match seed {
+                0 => XorShift32::DEFAULT_SEED,
+                seed => seed,
+            },
+        }
+    }
+}
+
+impl Default for XorShift32 {
+    fn default() -> Self {
+        XorShift32::new(XorShift32::DEFAULT_SEED)
+    }
+}
+
+impl Random for XorShift32 {
+    fn next_u32(&mut self) -> u32 {
+        self.state ^= self.state << 13;
+        self.state ^= self.state >> 17;
+        self.state ^= self.state << 5;
+        self.state
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn same_seed_gives_same_numbers() {
+        let mut first = XorShift32::new(1234);
+        let mut second = XorShift32::new(1234);
+        for _ in 0..10 {
+            assert_eq!(first.next_u32(), second.next_u32());
+        }
+        assert_ne!(XorShift32::new(1).next_u32(), XorShift32::new(2).next_u32());
+    }
+
+    #[test]
+    fn zero_seed_is_replaced() {
+        assert_eq!(XorShift32::new(0), XorShift32::default());
+    }
+
+    #[test]
+    fn next_between_stays_within_limit() {
+        let mut random = XorShift32::default();
+        let mut seen = [false; 7];
+        for _ in 0..1000 {
+            let value = random.next_between(3);
+            assert!((-3..=3).contains(&value));
+            seen[(value + 3) as usize] = true;
+        }
+        assert_eq!(seen, [true; 7]);
+        assert_eq!(random.next_between(0), 0);
+    }
+
+    #[test]
+    fn next_between_takes_largest_limit() {
+        let mut random = XorShift32::default();
+        for _ in 0..1000 {
+            let value = random.next_between(u32::MAX);
+            assert!((-(u32::MAX as i64)..=u32::MAX as i64).contains(&value));
+        }
+    }
+}
@@ -0,0 +1,729 @@
+use core::fmt::Write;
+
+use embedded_graphics::prelude::Point;
+use embedded_graphics::text::Alignment;
+use heapless::{String, Vec};
+
+use super::computer_player::{ComputerPlayer, Difficulty};
+use super::game_objects::{
+    Game, GameOver, GameState, ScreenObject, ScreenText, CONTENT_CAPACITY, TEXT_CAPACITY,
+};
+use super::input::{
+    ButtonInput, InpuDirection, InputError, LeftRightPosition, PaddleInput, PaddleRow,
+    UserInteraction,
+};
+
+/// Ticks the new score is shown before the next serve.
+const POINT_SCORED_TICKS: u32 = 25;
+/// Ticks the winner is shown before the match is over, 3 s at 20 ms per tick.
+const MATCH_OVER_TICKS: u32 = 150;
+const LINE_HEIGHT: i32 = 12;
+
+/// Who plays the right paddle.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Mode {
+    /// Every paddle is played by a person, however many paddles there are.
+    Humans,
+    AgainstComputer(Difficulty),
+}
+
+impl Mode {
+    /// The choices on the mode select screen, top to bottom.
+    pub const ALL: [Mode; 4] = [
+        Mode::Humans,
+        Mode::AgainstComputer(Difficulty::EASY),
+        Mode::AgainstComputer(Difficulty::MEDIUM),
+        Mode::AgainstComputer(Difficulty::HARD),
+    ];
+
+    fn name(&self) -> &'static str {
+        match self {
+            Mode::Humans => "HUMANS",
+            Mode::AgainstComputer(Difficulty::EASY) => "EASY",
+            Mode::AgainstComputer(Difficulty::MEDIUM) => "MEDIUM",
+            Mode::AgainstComputer(Difficulty::HARD) => "HARD",
+            Mode::AgainstComputer(_) => "COMPUTER",
+        }
+    }
+}
+
+/// What the session is showing.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Screen {
+    Title,
+    /// `selected` is an index into `Mode::ALL`.
+    ModeSelect {
+        selected: usize,
+    },
+    Playing,
+    Paused,
+    /// The game waits until the controller of the paddle in `row` on `side` reads steadily again.
+    Unplugged {
+        side: LeftRightPosition,
+        row: PaddleRow,
+    },
+    PointScored {
+        scorer: LeftRightPosition,
+        ticks_left: u32,
+    },
+    MatchOver {
+        winner: LeftRightPosition,
+        ticks_left: u32,
+    },
+}
+
+/// Everything around the game itself: the title screen, choosing who to play against, pausing
+/// and showing who scored or won. Owns the `Game` and the computer player.
+pub struct Session {
+    game: Game,
+    computer: ComputerPlayer,
+    mode: Mode,
+    screen: Screen,
+    has_button: bool,
+    last_menu_input: InpuDirection,
+}
+
+impl Session {
+    /// Starts at the title screen. The button moves on through the menus, serves without waiting
+    /// for the serve delay and otherwise pauses the game. `mode` is selected to begin with.
+    pub fn new(game: Game, computer: ComputerPlayer, mode: Mode) -> Session {
+        Session {
+            game,
+            computer,
+            mode,
+            screen: Screen::Title,
+            has_button: true,
+            last_menu_input: InpuDirection::Stay,
+        }
+    }
+
+    /// For boards without a button: no menus and no pausing, the game starts right away in
+    /// `mode` and a new match follows each one.
+    pub fn without_button(game: Game, computer: ComputerPlayer, mode: Mode) -> Session {
+        let mut session = Session {
+            screen: Screen::Playing,
+            has_button: false,
+            ..Session::new(game, computer, mode)
+        };
+        session.start_match();
+        session
+    }
+
+    pub fn get_screen(&self) -> Screen {
+        self.screen
+    }
+    pub fn get_mode(&self) -> Mode {
+        self.mode
+    }
+    pub fn get_game(&self) -> &Game {
+        &self.game
+    }
+
+    /// Moves on by `ticks` simulation ticks.
+    pub fn update<U: UserInteraction, B: ButtonInput>(
+        &mut self,
+        ticks: u32,
+        players: &mut U,
+        button: &mut B,
+    ) {
+        let button_pressed = button.was_pressed() && self.has_button;
+        self.screen = match self.screen {
+            Screen::Title => match button_pressed {
+                true => Screen::ModeSelect {
+                    selected: Mode::ALL
+                        .iter()
+                        .position(|mode| *mode == self.mode)
+                        .unwrap_or(0),
+                },
+                false => Screen::Title,
+            },
+            Screen::ModeSelect { selected } => self.select_mode(selected, players, button_pressed),
+            Screen::Playing => match (button_pressed, self.game.get_ticks_until_serve() > 0) {
+                (true, true) => {
+                    self.game.serve_now();
+                    self.play(ticks, players)
+                }
+                (true, false) => Screen::Paused,
+                (false, _) => self.play(ticks, players),
+            },
+            Screen::Paused => match button_pressed {
+                true => Screen::Playing,
+                false => Screen::Paused,
+            },
+            Screen::Unplugged { side, row } => match paddle_input(players, &side, &row) {
+                Err(InputError::Disconnected) => Screen::Unplugged { side, row },
+                _ => Screen::Playing,
+            },
+            Screen::PointScored { scorer, ticks_left } => match ticks_left.saturating_sub(ticks) {
+                0 => Screen::Playing,
+                ticks_left => Screen::PointScored { scorer, ticks_left },
+            },
+            Screen::MatchOver { winner, ticks_left } => {
+                match (ticks_left.saturating_sub(ticks), self.has_button) {
+                    (0, true) => Screen::Title,
+                    (0, false) => {
+                        self.start_match();
+                        Screen::Playing
+                    }
+                    (ticks_left, _) => Screen::MatchOver { winner, ticks_left },
+                }
+            }
+        };
+    }
+
+    /// What to draw for the current screen, ready for `Graphics::redraw`.
+    pub fn get_content_to_display(&mut self) -> Vec<ScreenObject, CONTENT_CAPACITY> {
+        let mut content: Vec<ScreenObject, CONTENT_CAPACITY> = Vec::new();
+        match self.screen {
+            Screen::Title => {
+                content.push(self.text_line(-1, "PONG")).unwrap();
+                content.push(self.text_line(1, "PRESS BUTTON")).unwrap();
+            }
+            Screen::ModeSelect { selected } => {
+                let first_line = -(Mode::ALL.len() as i32) / 2;
+                for (index, mode) in Mode::ALL.iter().enumerate() {
+                    let mut line: String<TEXT_CAPACITY> = String::new();
+                    match index == selected {
+                        true => write!(line, "> {} <", mode.name()).unwrap(),
+                        false => write!(line, "{}", mode.name()).unwrap(),
+                    }
+                    content
+                        .push(self.text_line(first_line + index as i32, &line))
+                        .unwrap();
+                }
+            }
+            Screen::Playing => content = self.game.get_content_to_display(),
+            Screen::Paused => {
+                content = self.game.get_content_to_display();
+                content.push(self.text_line(-2, "PAUSED")).unwrap();
+            }
+            Screen::Unplugged { side, .. } => {
+                content = self.game.get_content_to_display();
+                let message = match side {
+                    LeftRightPosition::Left => "LEFT UNPLUGGED",
+                    LeftRightPosition::Right => "RIGHT UNPLUGGED",
+                    LeftRightPosition::Top => "TOP UNPLUGGED",
+                    LeftRightPosition::Bottom => "BOTTOM UNPLUGGED",
+                };
+                content.push(self.text_line(-2, message)).unwrap();
+            }
+            Screen::PointScored { scorer, .. } => {
+                content = self.game.get_content_to_display();
+                let message = match scorer {
+                    LeftRightPosition::Left => "LEFT SCORES",
+                    LeftRightPosition::Right => "RIGHT SCORES",
+                    LeftRightPosition::Top => "TOP SCORES",
+                    LeftRightPosition::Bottom => "BOTTOM SCORES",
+                };
+                content.push(self.text_line(-2, message)).unwrap();
+            }
+            Screen::MatchOver { winner, .. } => {
+                content = self.game.get_content_to_display();
+                let message = match winner {
+                    LeftRightPosition::Left => "LEFT WINS",
+                    LeftRightPosition::Right => "RIGHT WINS",
+                    LeftRightPosition::Top => "TOP WINS",
+                    LeftRightPosition::Bottom => "BOTTOM WINS",
+                };
+                content.push(self.text_line(-2, message)).unwrap();
+            }
+        }
+        content
+    }
+
+    fn select_mode<U: UserInteraction>(
+        &mut self,
+        selected: usize,
+        players: &mut U,
+        button_pressed: bool,
+    ) -> Screen {
+        if button_pressed {
+            self.mode = Mode::ALL[selected];
+            self.start_match();
+            return Screen::Playing;
+        }
+        // The left paddle's control moves through the choices, one step each time it is pushed.
+        let input = players
+            .get_input_direction(&LeftRightPosition::Left)
+            .unwrap_or(InpuDirection::Stay);
+        let choices = Mode::ALL.len();
+        let selected = match (&input, &self.last_menu_input) {
+            (InpuDirection::Up, InpuDirection::Stay) => (selected + choices - 1) % choices,
+            (InpuDirection::Down, InpuDirection::Stay) => (selected + 1) % choices,
+            _ => selected,
+        };
+        self.last_menu_input = input;
+        Screen::ModeSelect { selected }
+    }
+
+    fn start_match(&mut self) {
+        if let Mode::AgainstComputer(difficulty) = self.mode {
+            self.computer.set_difficulty(difficulty);
+        }
+        self.game.start_new_match();
+    }
+
+    fn play<U: UserInteraction>(&mut self, ticks: u32, players: &mut U) -> Screen {
+        self.game.set_time_step(ticks);
+        players.observe_game(&self.game);
+        self.computer.observe_game(&self.game);
+        for side in self.game.get_sides().iter() {
+            let rows = self.game.get_paddles(side).len();
+            for row in PaddleRow::ALL.iter().take(rows) {
+                let input = match (side, self.mode) {
+                    (LeftRightPosition::Right, Mode::AgainstComputer(_)) => {
+                        paddle_input(&mut self.computer, side, row)
+                    }
+                    _ => paddle_input(players, side, row),
+                };
+                match input {
+                    Ok(PaddleInput::Direction(direction)) => {
+                        self.game.move_paddle(side, row, direction)
+                    }
+                    Ok(PaddleInput::Analog {
+                        direction,
+                        magnitude,
+                    }) => self
+                        .game
+                        .move_paddle_at_speed(side, row, direction, magnitude),
+                    Ok(PaddleInput::Position(position)) => {
+                        self.game.move_paddle_to(side, row, position)
+                    }
+                    Err(InputError::Disconnected) => {
+                        return Screen::Unplugged {
+                            side: *side,
+                            row: *row,
+                        }
+                    }
+                    // A reading that makes no sense leaves the paddle where it is.
+                    Err(InputError::OutOfRange(_)) => {
+                        self.game.move_paddle(side, row, InpuDirection::Stay)
+                    }
+                }
+            }
+        }
+        match self.game.let_ball_move() {
+            GameState::Ongoing => Screen::Playing,
+            GameState::PointScored(scorer) => {
+                self.game.start_new_game();
+                Screen::PointScored {
+                    scorer: side_of(&scorer),
+                    ticks_left: POINT_SCORED_TICKS,
+                }
+            }
+            GameState::Finnished(winner) => Screen::MatchOver {
+                winner: side_of(&winner),
+                ticks_left: MATCH_OVER_TICKS,
+            },
+        }
+    }
+
+    /// Centred text, `line` lines below the middle of the screen.
+    fn text_line(&self, line: i32, text: &str) -> ScreenObject {
+        let center = self.game.get_screen_dimensions().center();
+        let position = Point {
+            x: center.x,
+            y: center.y + line * LINE_HEIGHT,
+        };
+        ScreenObject::Text(ScreenText::new(position, text, Alignment::Center))
+    }
+}
+
+fn paddle_input<U: UserInteraction>(
+    players: &mut U,
+    side: &LeftRightPosition,
+    row: &PaddleRow,
+) -> Result<PaddleInput, InputError> {
+    match row {
+        PaddleRow::Front => players.get_paddle_input(side),
+        PaddleRow::Back => players.get_back_paddle_input(side),
+    }
+}
+
+fn side_of(winner: &GameOver) -> LeftRightPosition {
+    match winner {
+        GameOver::LeftWins => LeftRightPosition::Left,
+        GameOver::RightWins => LeftRightPosition::Right,
+        GameOver::TopWins => LeftRightPosition::Top,
+        GameOver::BottomWins => LeftRightPosition::Bottom,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use embedded_graphics::prelude::Size;
+
+    use super::*;
+    use crate::game_objects::GameBuilder;
+    use crate::physics::{TimeTick, Velocity};
+
+    /// Pushes the left control in the given direction.
+    struct Joystick(InpuDirection);
+
+    impl UserInteraction for Joystick {
+        fn get_input_direction(
+            &mut self,
+            user_position: &LeftRightPosition,
+        ) -> Result<InpuDirection, InputError> {
+            Ok(match (user_position, &self.0) {
+                (LeftRightPosition::Left, InpuDirection::Up) => InpuDirection::Up,
+                (LeftRightPosition::Left, InpuDirection::Down) => InpuDirection::Down,
+                _ => InpuDirection::Stay,
+            })
+        }
+    }
+
+    /// The right controller is unplugged, or plugged in and left alone.
+    struct Unplugged(bool);
+
+    impl UserInteraction for Unplugged {
+        fn get_input_direction(
+            &mut self,
+            user_position: &LeftRightPosition,
+        ) -> Result<InpuDirection, InputError> {
+            match (user_position, self.0) {
+                (LeftRightPosition::Right, true) => Err(InputError::Disconnected),
+                _ => Ok(InpuDirection::Stay),
+            }
+        }
+    }
+
+    /// Reports a press on every update, or never.
+    struct Button(bool);
+
+    impl ButtonInput for Button {
+        fn was_pressed(&mut self) -> bool {
+            self.0
+        }
+    }
+
+    fn game() -> Game {
+        GameBuilder::new(160, 128)
+            .ball_radius(3)
+            .paddle_size(Size {
+                width: 6,
+                height: 40,
+            })
+            .time_tick(TimeTick {
+                max_ball_movement: 5,
+                max_paddle_movement: 5,
+                time_step: 1,
+            })
+            .initial_ball_velocity(Velocity::from_pixels(5, 0))
+            .points_to_win(2)
+            .build()
+    }
+
+    fn session() -> Session {
+        let computer = ComputerPlayer::new(LeftRightPosition::Right, Difficulty::EASY);
+        Session::new(game(), computer, Mode::Humans)
+    }
+
+    fn press_button(session: &mut Session) {
+        session.update(1, &mut Joystick(InpuDirection::Stay), &mut Button(true));
+    }
+
+    fn texts(content: &[ScreenObject]) -> impl Iterator<Item = &str> {
+        content.iter().filter_map(|object| match object {
+            ScreenObject::Text(text) => Some(text.content.as_str()),
+            _ => None,
+        })
+    }
+
+    #[test]
+    fn button_leads_from_title_through_mode_select_to_playing() {
+        let mut session = session();
+        assert_eq!(session.get_screen(), Screen::Title);
+        assert!(texts(&session.get_content_to_display()).any(|text| text == "PONG"));
+
+        press_button(&mut session);
+        assert_eq!(session.get_screen(), Screen::ModeSelect { selected: 0 });
+        press_button(&mut session);
+        assert_eq!(session.get_screen(), Screen::Playing);
+        assert_eq!(session.get_mode(), Mode::Humans);
+    }
+
+    #[test]
+    fn mode_select_moves_once_per_push() {
+        let mut session = session();
+        press_button(&mut session);
+        for _ in 0..3 {
+            session.update(1, &mut Joystick(InpuDirection::Down), &mut Button(false));
+        }
+        assert_eq!(session.get_screen(), Screen::ModeSelect { selected: 1 });
+        session.update(1, &mut Joystick(InpuDirection::Stay), &mut Button(false));
+        session.update(1, &mut Joystick(InpuDirection::Down), &mut Button(false));
+        assert!(texts(&session.get_content_to_display()).any(|text| text == "> MEDIUM <"));
+
+        session.update(1, &mut Joystick(InpuDirection::Stay), &mut Button(false));
+        session.update(1, &mut Joystick(InpuDirection::Up), &mut Button(false));
+        session.update(1, &mut Joystick(InpuDirection::Stay), &mut Button(false));
+        session.update(1, &mut Joystick(InpuDirection::Up), &mut Button(false));
+        session.update(1, &mut Joystick(InpuDirection::Stay), &mut Button(false));
+        session.update(1, &mut Joystick(InpuDirection::Up), &mut Button(false));
+        assert_eq!(session.get_screen(), Screen::ModeSelect { selected: 3 });
+
+        press_button(&mut session);
+        assert_eq!(session.get_mode(), Mode::AgainstComputer(Difficulty::HARD));
+    }
+
+    #[test]
+    fn pause_stops_the_ball() {
+        let mut session = session();
+        press_button(&mut session);
+        press_button(&mut session);
+        press_button(&mut session);
+        assert_eq!(session.get_screen(), Screen::Paused);
+        assert!(texts(&session.get_content_to_display()).any(|text| text == "PAUSED"));
+
+        let ball = session.get_game().get_ball().position;
+        session.update(5, &mut Joystick(InpuDirection::Stay), &mut Button(false));
+        assert_eq!(session.get_game().get_ball().position, ball);
+
+        press_button(&mut session);
+        session.update(1, &mut Joystick(InpuDirection::Stay), &mut Button(false));
+        assert_ne!(session.get_game().get_ball().position, ball);
+    }
+
+    #[test]
+    fn unplugged_controller_stops_the_game_until_it_is_back() {
+        let mut session = session();
+        press_button(&mut session);
+        press_button(&mut session);
+        session.update(1, &mut Unplugged(true), &mut Button(false));
+        assert_eq!(
+            session.get_screen(),
+            Screen::Unplugged {
+                side: LeftRightPosition::Right,
+                row: PaddleRow::Front
+            }
+        );
+        assert!(texts(&session.get_content_to_display()).any(|text| text == "RIGHT UNPLUGGED"));
+
+        let ball = session.get_game().get_ball().position;
+        session.update(5, &mut Unplugged(true), &mut Button(true));
+        assert_eq!(session.get_game().get_ball().position, ball);
+
+        session.update(1, &mut Unplugged(false), &mut Button(false));
+        assert_eq!(session.get_screen(), Screen::Playing);
+        session.update(1, &mut Unplugged(false), &mut Button(false));
+        assert_ne!(session.get_game().get_ball().position, ball);
+    }
+
+    #[test]
+    fn unplugged_controller_is_ignored_against_computer() {
+        let computer = ComputerPlayer::new(LeftRightPosition::Right, Difficulty::EASY);
+        let mut session =
+            Session::without_button(game(), computer, Mode::AgainstComputer(Difficulty::EASY));
+        session.update(1, &mut Unplugged(true), &mut Button(false));
+        assert_eq!(session.get_screen(), Screen::Playing);
+    }
+
+    /// Every control pushed down, which is right for the top and bottom paddles.
+    struct AllDown;
+
+    impl UserInteraction for AllDown {
+        fn get_input_direction(
+            &mut self,
+            _user_position: &LeftRightPosition,
+        ) -> Result<InpuDirection, InputError> {
+            Ok(InpuDirection::Down)
+        }
+    }
+
+    #[test]
+    fn four_player_game_moves_all_four_paddles() {
+        let computer = ComputerPlayer::new(LeftRightPosition::Right, Difficulty::EASY);
+        let game = GameBuilder::new(160, 128)
+            .paddle_size(Size {
+                width: 6,
+                height: 40,
+            })
+            .time_tick(TimeTick {
+                max_ball_movement: 5,
+                max_paddle_movement: 5,
+                time_step: 1,
+            })
+            .four_players(true)
+            .build();
+        let mut session = Session::new(game, computer, Mode::Humans);
+        press_button(&mut session);
+        assert!(texts(&session.get_content_to_display()).any(|text| text == "> HUMANS <"));
+        press_button(&mut session);
+
+        session.update(1, &mut AllDown, &mut Button(false));
+        let paddle_at = |side| {
+            let paddle = session.get_game().get_paddle(&side).unwrap();
+            paddle.top_left_pos.round()
+        };
+        assert_eq!(paddle_at(LeftRightPosition::Left), Point { x: 0, y: 11 });
+        assert_eq!(paddle_at(LeftRightPosition::Right), Point { x: 154, y: 11 });
+        assert_eq!(paddle_at(LeftRightPosition::Top), Point { x: 11, y: 0 });
+        assert_eq!(
+            paddle_at(LeftRightPosition::Bottom),
+            Point { x: 11, y: 122 }
+        );
+    }
+
+    /// Only the back controls are pushed, down, and the left back one is unplugged if asked.
+    struct BackDown {
+        left_unplugged: bool,
+    }
+
+    impl UserInteraction for BackDown {
+        fn get_input_direction(
+            &mut self,
+            _user_position: &LeftRightPosition,
+        ) -> Result<InpuDirection, InputError> {
+            Ok(InpuDirection::Stay)
+        }
+        fn get_back_paddle_input(
+            &mut self,
+            user_position: &LeftRightPosition,
+        ) -> Result<PaddleInput, InputError> {
+            match (user_position, self.left_unplugged) {
+                (LeftRightPosition::Left, true) => Err(InputError::Disconnected),
+                _ => Ok(PaddleInput::Direction(InpuDirection::Down)),
+            }
+        }
+    }
+
+    #[test]
+    fn doubles_paddles_follow_their_own_controls() {
+        let computer = ComputerPlayer::new(LeftRightPosition::Right, Difficulty::EASY);
+        let game = GameBuilder::new(160, 128)
+            .paddle_size(Size {
+                width: 6,
+                height: 40,
+            })
+            .time_tick(TimeTick {
+                max_ball_movement: 5,
+                max_paddle_movement: 5,
+                time_step: 1,
+            })
+            .doubles(true)
+            .build();
+        let mut session = Session::new(game, computer, Mode::Humans);
+        press_button(&mut session);
+        assert!(texts(&session.get_content_to_display()).any(|text| text == "> HUMANS <"));
+        press_button(&mut session);
+
+        let mut players = BackDown {
+            left_unplugged: false,
+        };
+        session.update(1, &mut players, &mut Button(false));
+        for side in [LeftRightPosition::Left, LeftRightPosition::Right] {
+            let paddles = session.get_game().get_paddles(&side);
+            assert_eq!(paddles[0].top_left_pos.y.round(), 0);
+            assert_eq!(paddles[1].top_left_pos.y.round(), 5);
+        }
+
+        players.left_unplugged = true;
+        session.update(1, &mut players, &mut Button(false));
+        assert_eq!(
+            session.get_screen(),
+            Screen::Unplugged {
+                side: LeftRightPosition::Left,
+                row: PaddleRow::Back
+            }
+        );
+    }
+
+    fn play_until_point(session: &mut Session) {
+        for _ in 0..100 {
+            session.update(1, &mut Joystick(InpuDirection::Stay), &mut Button(false));
+            if session.get_screen() != Screen::Playing {
+                return;
+            }
+        }
+        panic!("No point scored");
+    }
+
+    #[test]
+    fn points_and_match_over_are_shown_for_a_while() {
+        let mut session = session();
+        press_button(&mut session);
+        press_button(&mut session);
+
+        // The ball flies past the right paddle, which stays at the top.
+        play_until_point(&mut session);
+        assert_eq!(
+            session.get_screen(),
+            Screen::PointScored {
+                scorer: LeftRightPosition::Left,
+                ticks_left: POINT_SCORED_TICKS
+            }
+        );
+        assert!(texts(&session.get_content_to_display()).any(|text| text == "LEFT SCORES"));
+        session.update(
+            POINT_SCORED_TICKS,
+            &mut Joystick(InpuDirection::Stay),
+            &mut Button(false),
+        );
+        assert_eq!(session.get_screen(), Screen::Playing);
+
+        play_until_point(&mut session);
+        assert!(matches!(
+            session.get_screen(),
+            Screen::MatchOver {
+                winner: LeftRightPosition::Left,
+                ..
+            }
+        ));
+        assert!(texts(&session.get_content_to_display()).any(|text| text == "LEFT WINS"));
+        session.update(
+            MATCH_OVER_TICKS,
+            &mut Joystick(InpuDirection::Stay),
+            &mut Button(false),
+        );
+        assert_eq!(session.get_screen(), Screen::Title);
+    }
+
+    #[test]
+    fn button_serves_while_ball_waits() {
+        let computer = ComputerPlayer::new(LeftRightPosition::Right, Difficulty::EASY);
+        let game = GameBuilder::new(160, 128)
+            .time_tick(TimeTick {
+                max_ball_movement: 5,
+                max_paddle_movement: 5,
+                time_step: 1,
+            })
+            .initial_ball_velocity(Velocity::from_pixels(5, 0))
+            .serve_delay(50)
+            .build();
+        let mut session = Session::new(game, computer, Mode::Humans);
+        press_button(&mut session);
+        press_button(&mut session);
+        assert_eq!(session.get_game().get_ticks_until_serve(), 50);
+
+        press_button(&mut session);
+        assert_eq!(session.get_screen(), Screen::Playing);
+        assert_eq!(session.get_game().get_ticks_until_serve(), 0);
+        press_button(&mut session);
+        assert_eq!(session.get_screen(), Screen::Paused);
+    }
+
+    #[test]
+    fn without_button_there_are_no_menus() {
+        let computer = ComputerPlayer::new(LeftRightPosition::Right, Difficulty::EASY);
+        let mut session = Session::without_button(game(), computer, Mode::Humans);
+        assert_eq!(session.get_screen(), Screen::Playing);
+        press_button(&mut session);
+        assert_eq!(session.get_screen(), Screen::Playing);
+
+        play_until_point(&mut session);
+        session.update(
+            POINT_SCORED_TICKS,
+            &mut Joystick(InpuDirection::Stay),
+            &mut Button(false),
+        );
+        play_until_point(&mut session);
+        session.update(
+            MATCH_OVER_TICKS,
+            &mut Joystick(InpuDirection::Stay),
+            &mut Button(false),
+        );
+        assert_eq!(session.get_screen(), Screen::Playing);
+        assert_eq!(session.get_game().get_score(), Default::default());
+    }
+}
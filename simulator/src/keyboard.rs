@@ -0,0 +1,142 @@
+use std::io;
+use std::time::{Duration, Instant};
+
+use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind};
+use pong::input::{
+    ButtonInput, InpuDirection, InputError, LeftRightPosition, PaddleInput, UserInteraction,
+};
+
+// Most terminals only report key presses, so a held key is seen as a stream of repeated
+// presses. Keep moving for a while after the last one to bridge the gap between repeats.
+const KEY_HOLD_TIME: Duration = Duration::from_millis(150);
+
+#[derive(Clone, Copy, Default)]
+enum HeldKey {
+    Up(Instant),
+    Down(Instant),
+    #[default]
+    None,
+}
+
+/// Keyboard controls: `w`/`s` for the left player, arrow up/down for the right player, `a`/`d`
+/// for the top player, arrow left/right for the bottom player and space for the button. In
+/// doubles `r`/`f` and `i`/`k` are for the left and right back players.
+#[derive(Default)]
+pub struct KeyboardInput {
+    left_user: HeldKey,
+    right_user: HeldKey,
+    top_user: HeldKey,
+    bottom_user: HeldKey,
+    left_back_user: HeldKey,
+    right_back_user: HeldKey,
+    button_pressed: bool,
+}
+
+pub enum KeyboardCommand {
+    Continue,
+    NextTheme,
+    Quit,
+}
+
+impl KeyboardInput {
+    /// Handles all pending key events without blocking.
+    pub fn poll(&mut self) -> io::Result<KeyboardCommand> {
+        let mut command = KeyboardCommand::Continue;
+        while event::poll(Duration::ZERO)? {
+            if let Event::Key(key) = event::read()? {
+                match self.handle_key(key) {
+                    KeyboardCommand::Quit => return Ok(KeyboardCommand::Quit),
+                    KeyboardCommand::NextTheme => command = KeyboardCommand::NextTheme,
+                    KeyboardCommand::Continue => {}
+                }
+            }
+        }
+        Ok(command)
+    }
+
+    /// The button presses seen by `poll` since the last call.
+    pub fn button(&mut self) -> KeyboardButton {
+        KeyboardButton {
+            pressed: std::mem::take(&mut self.button_pressed),
+        }
+    }
+
+    fn handle_key(&mut self, key: KeyEvent) -> KeyboardCommand {
+        let now = Instant::now();
+        let pressed = key.kind != KeyEventKind::Release;
+        let (player, held_key) = match key.code {
+            KeyCode::Char('q') | KeyCode::Esc => return KeyboardCommand::Quit,
+            KeyCode::Char('t') if pressed => return KeyboardCommand::NextTheme,
+            KeyCode::Char(' ') | KeyCode::Enter => {
+                self.button_pressed |= pressed;
+                return KeyboardCommand::Continue;
+            }
+            KeyCode::Char('w') => (&mut self.left_user, HeldKey::Up(now)),
+            KeyCode::Char('s') => (&mut self.left_user, HeldKey::Down(now)),
+            KeyCode::Up => (&mut self.right_user, HeldKey::Up(now)),
+            KeyCode::Down => (&mut self.right_user, HeldKey::Down(now)),
+            // The top and bottom paddles move sideways, up is left.
+            KeyCode::Char('a') => (&mut self.top_user, HeldKey::Up(now)),
+            KeyCode::Char('d') => (&mut self.top_user, HeldKey::Down(now)),
+            KeyCode::Left => (&mut self.bottom_user, HeldKey::Up(now)),
+            KeyCode::Right => (&mut self.bottom_user, HeldKey::Down(now)),
+            KeyCode::Char('r') => (&mut self.left_back_user, HeldKey::Up(now)),
+            KeyCode::Char('f') => (&mut self.left_back_user, HeldKey::Down(now)),
+            KeyCode::Char('i') => (&mut self.right_back_user, HeldKey::Up(now)),
+            KeyCode::Char('k') => (&mut self.right_back_user, HeldKey::Down(now)),
+            _ => return KeyboardCommand::Continue,
+        };
+        *player = match pressed {
+            true => held_key,
+            false => HeldKey::None,
+        };
+        KeyboardCommand::Continue
+    }
+}
+
+impl UserInteraction for KeyboardInput {
+    fn get_input_direction(
+        &mut self,
+        user_position: &LeftRightPosition,
+    ) -> Result<InpuDirection, InputError> {
+        let held_key = match user_position {
+            LeftRightPosition::Left => self.left_user,
+            LeftRightPosition::Right => self.right_user,
+            LeftRightPosition::Top => self.top_user,
+            LeftRightPosition::Bottom => self.bottom_user,
+        };
+        Ok(direction_of(held_key))
+    }
+    fn get_back_paddle_input(
+        &mut self,
+        user_position: &LeftRightPosition,
+    ) -> Result<PaddleInput, InputError> {
+        let held_key = match user_position {
+            LeftRightPosition::Left => self.left_back_user,
+            LeftRightPosition::Right => self.right_back_user,
+            // There aren't keys enough for eight players, these follow the front paddles.
+            LeftRightPosition::Top => self.top_user,
+            LeftRightPosition::Bottom => self.bottom_user,
+        };
+        Ok(PaddleInput::Direction(direction_of(held_key)))
+    }
+}
+
+fn direction_of(held_key: HeldKey) -> InpuDirection {
+    match held_key {
+        HeldKey::Up(pressed_at) if pressed_at.elapsed() < KEY_HOLD_TIME => InpuDirection::Up,
+        HeldKey::Down(pressed_at) if pressed_at.elapsed() < KEY_HOLD_TIME => InpuDirection::Down,
+        _ => InpuDirection::Stay,
+    }
+}
+
+/// Space or enter, standing in for the board's button.
+pub struct KeyboardButton {
+    pressed: bool,
+}
+
+impl ButtonInput for KeyboardButton {
+    fn was_pressed(&mut self) -> bool {
+        std::mem::take(&mut self.pressed)
+    }
+}
@@ -0,0 +1,204 @@
+use std::io;
+use std::thread;
+use std::time::{Duration, Instant, SystemTime};
+
+use crossterm::{cursor, execute, terminal};
+use embedded_graphics::prelude::Size;
+use pong::computer_player::{ComputerPlayer, Difficulty};
+use pong::game_objects::*;
+use pong::graphics::{Display, Graphics, Theme};
+use pong::input::LeftRightPosition;
+use pong::physics::{FixedTimestep, PaddleBounce, SpeedUp, TimeTick, Velocity};
+use pong::random::XorShift32;
+use pong::session::{Mode, Session};
+
+mod keyboard;
+mod screen;
+
+use keyboard::{KeyboardCommand, KeyboardInput};
+use screen::TerminalScreen;
+
+// Same dimensions as the ST7735 in landscape orientation.
+const X_PIXELS: u32 = 160;
+const Y_PIXELS: u32 = 128;
+// Same tick length as the firmware, so the game runs at the same speed.
+const TICK_LENGTH_US: u32 = 20_000;
+const MAX_TICKS_PER_FRAME: u32 = 4;
+const SERVE_DELAY_TICKS: u32 = 50;
+
+/// Command line options.
+struct Options {
+    mode: Mode,
+    four_players: bool,
+    doubles: bool,
+    theme: Theme,
+    seed: u32,
+}
+
+fn main() -> io::Result<()> {
+    let options = match parse_options() {
+        Ok(options) => options,
+        Err(message) => {
+            eprintln!("{}", message);
+            eprintln!(
+                "Usage: simulator [--computer easy|medium|hard] [--four-players] [--doubles] \
+                 [--theme classic|high-contrast|color-blind|paper] [--seed <number>]"
+            );
+            std::process::exit(2);
+        }
+    };
+    let paddle_width = 6u32;
+    let time_tick = TimeTick {
+        max_ball_movement: 5,
+        max_paddle_movement: 5,
+        time_step: 1,
+    };
+
+    let pong: Game = GameBuilder::new(X_PIXELS, Y_PIXELS)
+        .ball_radius(3)
+        .paddle_size(Size {
+            width: paddle_width,
+            height: 40,
+        })
+        .time_tick(time_tick)
+        .initial_ball_velocity(Velocity::from_pixels(1, 1))
+        .paddle_bounce(PaddleBounce {
+            max_vertical_speed: 2,
+            paddle_movement_speed: 1,
+        })
+        .ball_speed_up(SpeedUp::Linear {
+            hits_per_increase: 4,
+        })
+        .serve_delay(SERVE_DELAY_TICKS)
+        .four_players(options.four_players)
+        .doubles(options.doubles)
+        .random(XorShift32::new(options.seed))
+        .build();
+    // Not the game's seed, so that the computer's mistakes don't follow the serves.
+    let computer = ComputerPlayer::with_random(
+        LeftRightPosition::Right,
+        Difficulty::MEDIUM,
+        XorShift32::new(!options.seed),
+    );
+    let session = Session::new(pong, computer, options.mode);
+
+    let mut screen = TerminalScreen::new(Size {
+        width: X_PIXELS,
+        height: Y_PIXELS,
+    });
+
+    terminal::enable_raw_mode()?;
+    execute!(io::stdout(), terminal::EnterAlternateScreen, cursor::Hide)?;
+    let result = play(
+        session,
+        &mut screen,
+        KeyboardInput::default(),
+        options.theme,
+    );
+    execute!(io::stdout(), cursor::Show, terminal::LeaveAlternateScreen)?;
+    terminal::disable_raw_mode()?;
+    result
+}
+
+/// `--computer <difficulty>` picks playing against the computer in the menu, `--four-players` adds
+/// paddles on the top and bottom walls, `--doubles` a back paddle behind each one, `--theme <name>`
+/// picks the colours to start with and `--seed <number>` replays the same serves, otherwise they
+/// differ every run.
+fn parse_options() -> Result<Options, String> {
+    let mut options = Options {
+        mode: Mode::Humans,
+        four_players: false,
+        doubles: false,
+        theme: Theme::default(),
+        seed: SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .map_or(0, |time| time.subsec_nanos()),
+    };
+    let mut args = std::env::args().skip(1).peekable();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--computer" => {
+                // The difficulty may be left out.
+                let difficulty = match args.next_if(|arg| !arg.starts_with("--")).as_deref() {
+                    Some("easy") => Difficulty::EASY,
+                    Some("medium") | None => Difficulty::MEDIUM,
+                    Some("hard") => Difficulty::HARD,
+                    Some(other) => return Err(format!("Unknown difficulty: {}", other)),
+                };
+                options.mode = Mode::AgainstComputer(difficulty);
+            }
+            "--four-players" => options.four_players = true,
+            "--doubles" => options.doubles = true,
+            "--theme" => {
+                options.theme = match args.next().as_deref() {
+                    Some("classic") => Theme::CLASSIC,
+                    Some("high-contrast") => Theme::HIGH_CONTRAST,
+                    Some("color-blind") => Theme::COLOR_BLIND,
+                    Some("paper") => Theme::PAPER,
+                    Some(other) => return Err(format!("Unknown theme: {}", other)),
+                    None => return Err("Missing theme".to_string()),
+                };
+            }
+            "--seed" => {
+                options.seed = match args.next() {
+                    Some(seed) => seed
+                        .parse()
+                        .map_err(|_| format!("Seed must be a number: {}", seed))?,
+                    None => return Err("Missing seed".to_string()),
+                };
+            }
+            other => return Err(format!("Unknown argument: {}", other)),
+        }
+    }
+    Ok(options)
+}
+
+/// Host counterpart of `play()` in the firmware.
+fn play(
+    mut session: Session,
+    screen: &mut TerminalScreen,
+    mut user_input: KeyboardInput,
+    theme: Theme,
+) -> io::Result<()> {
+    let status_line = "w/s, up/down, a/d, left/right, r/f and i/k to move, space for menus and \
+                       pause, t to change colours, q to quit";
+    let mut display = Display {
+        display: screen,
+        theme,
+    };
+    display.set_theme(theme);
+    let mut timestep = FixedTimestep::new(TICK_LENGTH_US, MAX_TICKS_PER_FRAME);
+    let mut last_frame = Instant::now();
+    let mut on_screen_objects = session.get_content_to_display();
+    display.draw(&on_screen_objects);
+    loop {
+        let now = Instant::now();
+        let elapsed_us = now.duration_since(last_frame).as_micros() as u32;
+        last_frame = now;
+        let ticks = timestep.advance(elapsed_us);
+        if ticks == 0 {
+            thread::sleep(Duration::from_millis(1));
+            continue;
+        }
+
+        match user_input.poll()? {
+            KeyboardCommand::Continue => {}
+            KeyboardCommand::NextTheme => {
+                let current = Theme::BUILT_IN
+                    .iter()
+                    .position(|theme| *theme == display.theme);
+                let next = current.map_or(0, |index| (index + 1) % Theme::BUILT_IN.len());
+                display.set_theme(Theme::BUILT_IN[next]);
+                display.draw(&on_screen_objects);
+            }
+            KeyboardCommand::Quit => return Ok(()),
+        }
+        let mut button = user_input.button();
+        session.update(ticks, &mut user_input, &mut button);
+
+        let content = session.get_content_to_display();
+        display.redraw(&on_screen_objects, &content);
+        on_screen_objects = content;
+        display.display.flush(status_line)?;
+    }
+}
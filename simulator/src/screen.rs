@@ -0,0 +1,93 @@
+use std::io::{self, Stdout, Write};
+
+use crossterm::{
+    cursor, queue,
+    style::{self, Color, Print},
+};
+use embedded_graphics::{pixelcolor::Rgb565, prelude::*};
+
+/// An in-memory stand-in for the ST7735. Pixels are drawn into a buffer and written to the
+/// terminal on `flush`, two pixel rows per character cell using the upper half block.
+pub struct TerminalScreen {
+    size: Size,
+    pixels: Vec<Rgb565>,
+    out: Stdout,
+}
+
+impl TerminalScreen {
+    pub fn new(size: Size) -> TerminalScreen {
+        TerminalScreen {
+            size,
+            pixels: vec![Rgb565::BLACK; (size.width * size.height) as usize],
+            out: io::stdout(),
+        }
+    }
+
+    pub fn flush(&mut self, status_line: &str) -> io::Result<()> {
+        let width = self.size.width as usize;
+        let height = self.size.height as usize;
+        let mut current_colors = None;
+        for row in (0..height).step_by(2) {
+            queue!(self.out, cursor::MoveTo(0, (row / 2) as u16))?;
+            for column in 0..width {
+                let upper = self.pixels[row * width + column];
+                let lower = match row + 1 < height {
+                    true => self.pixels[(row + 1) * width + column],
+                    false => Rgb565::BLACK,
+                };
+                // Only emit escape codes when the colours change, most of the court is black.
+                if current_colors != Some((upper, lower)) {
+                    queue!(
+                        self.out,
+                        style::SetForegroundColor(to_terminal_color(upper)),
+                        style::SetBackgroundColor(to_terminal_color(lower))
+                    )?;
+                    current_colors = Some((upper, lower));
+                }
+                queue!(self.out, Print('▀'))?;
+            }
+        }
+        queue!(
+            self.out,
+            style::ResetColor,
+            cursor::MoveTo(0, height.div_ceil(2) as u16),
+            crossterm::terminal::Clear(crossterm::terminal::ClearType::CurrentLine),
+            Print(status_line)
+        )?;
+        self.out.flush()
+    }
+}
+
+fn to_terminal_color(color: Rgb565) -> Color {
+    // Scale the 5/6 bit channels up to 8 bits.
+    Color::Rgb {
+        r: color.r() << 3,
+        g: color.g() << 2,
+        b: color.b() << 3,
+    }
+}
+
+impl OriginDimensions for TerminalScreen {
+    fn size(&self) -> Size {
+        self.size
+    }
+}
+
+impl DrawTarget for TerminalScreen {
+    type Color = Rgb565;
+    type Error = core::convert::Infallible;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        let bounds = self.bounding_box();
+        for Pixel(point, color) in pixels.into_iter() {
+            if bounds.contains(point) {
+                let index = point.y as u32 * self.size.width + point.x as u32;
+                self.pixels[index as usize] = color;
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,125 @@
+use pong::filter::SampleFilter;
+use stm32f4xx_hal::{
+    adc::{
+        config::{AdcConfig, Continuous, Dma, SampleTime, Scan},
+        Adc,
+    },
+    dma::{config::DmaConfig, traits::StreamISR, DMAError, PeripheralToMemory, Stream0, Transfer},
+    pac::{ADC1, DMA2},
+};
+
+/// Readings per buffer, shared out between the controls. Twelve scans of two controls, six of
+/// four or three of eight, each control's readings in a buffer are filtered as one batch.
+pub const BUFFER_SAMPLES: usize = 24;
+/// What each control is sampled with, for `Adc::configure_channel`.
+pub const SAMPLE_TIME: SampleTime = SampleTime::Cycles_480;
+
+type Buffer = &'static mut [u16; BUFFER_SAMPLES];
+
+/// `N` players' controls, converted over and over by the ADC in scan mode. The DMA fills two
+/// buffers in turn without the CPU, `latest` swaps the spare buffer in for the one filled last
+/// and filters what is in it, so reading the controls never waits for the ADC.
+pub struct AdcScan<const N: usize> {
+    transfer: Transfer<Stream0<DMA2>, 0, Adc<ADC1>, PeripheralToMemory, Buffer>,
+    spare: Option<Buffer>,
+    filters: [SampleFilter; N],
+    latest: [u16; N],
+    /// The readings of the buffer filled last, one batch per channel.
+    batches: [[u16; BUFFER_SAMPLES]; N],
+    /// Channels whose batch hasn't been handed out by `read` yet.
+    unread: [bool; N],
+}
+
+impl<const N: usize> AdcScan<N> {
+    /// Every buffer starts with the first channel, so that the readings don't shift between them.
+    const SCANS_PER_BUFFER: usize = {
+        assert!(
+            N > 0 && BUFFER_SAMPLES.is_multiple_of(N),
+            "N must divide BUFFER_SAMPLES"
+        );
+        BUFFER_SAMPLES / N
+    };
+
+    /// Starts converting the `N` channels in `adc`'s sequence for good, the ADC can't do anything
+    /// else afterwards. The channels are put in the sequence with `Adc::configure_channel` first.
+    pub fn new(
+        mut adc: Adc<ADC1>,
+        stream: Stream0<DMA2>,
+        buffers: &'static mut [[u16; BUFFER_SAMPLES]; 3],
+        filter: SampleFilter,
+    ) -> AdcScan<N> {
+        assert_eq!(adc.sequence_length() as usize, N);
+        adc.apply_config(
+            AdcConfig::default()
+                .scan(Scan::Enabled)
+                .continuous(Continuous::Continuous)
+                .dma(Dma::Continuous),
+        );
+
+        let [first, second, spare] = buffers.each_mut();
+        // With double buffering the DMA goes round both buffers until it is stopped.
+        let config = DmaConfig::default()
+            .memory_increment(true)
+            .double_buffer(true);
+        let mut transfer =
+            Transfer::init_peripheral_to_memory(stream, adc, first, Some(second), config);
+        transfer.start(|adc| adc.start_conversion());
+
+        let mut scan = AdcScan {
+            transfer,
+            spare: Some(spare),
+            filters: [filter; N],
+            latest: [0; N],
+            batches: [[0; BUFFER_SAMPLES]; N],
+            unread: [false; N],
+        };
+        // A fraction of a millisecond, so that there is a reading from the start.
+        while !Stream0::<DMA2>::get_transfer_complete_flag() {}
+        scan.poll();
+        scan
+    }
+
+    /// The filtered reading of `channel`, counted in sequence order from 0, of the buffer filled
+    /// last, or the one before if none was filled since.
+    pub fn latest(&mut self, channel: usize) -> u16 {
+        self.poll();
+        self.latest[channel]
+    }
+
+    /// Like `latest`, along with the unfiltered readings it was made from. Those are only there
+    /// the first time after each new buffer, so that nothing looks at the same batch twice.
+    pub fn read(&mut self, channel: usize) -> (u16, Option<&[u16]>) {
+        self.poll();
+        let batch = match core::mem::replace(&mut self.unread[channel], false) {
+            true => Some(&self.batches[channel][..Self::SCANS_PER_BUFFER]),
+            false => None,
+        };
+        (self.latest[channel], batch)
+    }
+
+    fn poll(&mut self) {
+        let spare = match self.spare.take() {
+            Some(spare) => spare,
+            None => return,
+        };
+        self.spare = Some(match self.transfer.next_transfer(spare) {
+            Ok((filled, _)) => {
+                // The scans take turns, so the samples alternate between the channels.
+                let scans = Self::SCANS_PER_BUFFER;
+                for (channel, filter) in self.filters.iter_mut().enumerate() {
+                    let samples = &mut self.batches[channel];
+                    for (scan, sample) in samples[..scans].iter_mut().enumerate() {
+                        *sample = filled[scan * N + channel];
+                    }
+                    self.latest[channel] = filter.filter(&samples[..scans]);
+                    self.unread[channel] = true;
+                }
+                filled
+            }
+            // Nothing new, or the DMA got to the buffer first. Either way there's more next time.
+            Err(DMAError::NotReady(spare))
+            | Err(DMAError::Overrun(spare))
+            | Err(DMAError::SmallBuffer(spare)) => spare,
+        });
+    }
+}
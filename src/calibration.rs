@@ -0,0 +1,116 @@
+use cortex_m::delay::Delay;
+use embedded_graphics::prelude::Point;
+use embedded_graphics::text::Alignment;
+use heapless::Vec;
+use pong::calibration::{CalibrationRecorder, StoredCalibration};
+use pong::game_objects::{ScreenObject, ScreenText, CONTENT_CAPACITY};
+use pong::graphics::Graphics;
+use pong::input::ButtonInput;
+use stm32f4xx_hal::{
+    flash::{self, FlashExt},
+    pac::FLASH,
+};
+
+use crate::framebuffer::Flush;
+use crate::input::Control;
+
+/// The last 128K sector of the STM32F411's flash, `memory.x` keeps the program out of it.
+const CALIBRATION_SECTOR: u8 = 7;
+/// Where sector 7 starts, counted from the start of flash.
+const CALIBRATION_OFFSET: usize = 0x6_0000;
+/// Readings averaged for the centre once the button is pressed.
+const CENTER_SAMPLES: u32 = 16;
+/// Time between readings, also what the button's debouncing counts in.
+const SAMPLE_PERIOD_MS: u32 = 20;
+const LINE_HEIGHT: i32 = 12;
+
+/// `None` if the controllers were never calibrated.
+pub fn load(flash: &FLASH) -> Option<StoredCalibration> {
+    StoredCalibration::from_bytes(&flash.read()[CALIBRATION_OFFSET..])
+}
+
+pub fn save(flash: &mut FLASH, calibration: &StoredCalibration) -> Result<(), flash::Error> {
+    let mut unlocked = flash.unlocked();
+    unlocked.erase(CALIBRATION_SECTOR)?;
+    unlocked.program(CALIBRATION_OFFSET, calibration.to_bytes().iter())
+}
+
+/// Asks the players of `controls` to leave their sticks in the middle and then to move them to both
+/// ends, pressing the button after each step. Starts over until every stick travelled far enough.
+pub fn calibrate<G: Graphics + Flush, B: ButtonInput>(
+    display: &mut G,
+    screen_center: Point,
+    controls: &[Control],
+    mut read: impl FnMut(&Control) -> u16,
+    button: &mut B,
+    delay: &mut Delay,
+) -> StoredCalibration {
+    let mut shown = Vec::new();
+    let mut show = |display: &mut G, lines: &[&str]| {
+        display.clear(&shown);
+        shown = text_lines(screen_center, lines);
+        display.draw(&shown);
+        display.flush();
+    };
+    let mut retry = false;
+    loop {
+        let mut recorders = [CalibrationRecorder::new(); 8];
+        match retry {
+            true => show(display, &["Try again:", "centre sticks", "and press"]),
+            false => show(display, &["Centre sticks", "and press"]),
+        }
+        wait_for_press(button, delay, || {});
+        for _ in 0..CENTER_SAMPLES {
+            for (recorder, control) in recorders.iter_mut().zip(controls) {
+                recorder.record_center(read(control));
+            }
+        }
+
+        show(display, &["Move sticks to", "both ends", "and press"]);
+        wait_for_press(button, delay, || {
+            for (recorder, control) in recorders.iter_mut().zip(controls) {
+                recorder.record_travel(read(control));
+            }
+        });
+
+        let mut calibration = StoredCalibration::default();
+        let mut all_finished = true;
+        for (recorder, (side, row)) in recorders.iter().zip(controls) {
+            match recorder.finish() {
+                Some(finished) => *calibration.get_mut(side, row) = finished,
+                None => all_finished = false,
+            }
+        }
+        if all_finished {
+            show(display, &[]);
+            return calibration;
+        }
+        retry = true;
+    }
+}
+
+fn wait_for_press<B: ButtonInput>(
+    button: &mut B,
+    delay: &mut Delay,
+    mut each_sample: impl FnMut(),
+) {
+    while !button.was_pressed() {
+        each_sample();
+        delay.delay_ms(SAMPLE_PERIOD_MS);
+    }
+}
+
+fn text_lines(center: Point, lines: &[&str]) -> Vec<ScreenObject, CONTENT_CAPACITY> {
+    let first_line = center.y - (lines.len() as i32 - 1) * LINE_HEIGHT / 2;
+    lines
+        .iter()
+        .enumerate()
+        .map(|(line, text)| {
+            let position = Point {
+                x: center.x,
+                y: first_line + line as i32 * LINE_HEIGHT,
+            };
+            ScreenObject::Text(ScreenText::new(position, text, Alignment::Center))
+        })
+        .collect()
+}
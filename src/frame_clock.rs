@@ -0,0 +1,33 @@
+use stm32f4xx_hal::{
+    pac::TIM2,
+    prelude::*,
+    rcc::Clocks,
+    timer::{CounterUs, TimerExt},
+};
+
+/// Measures the real time between frames with TIM2 counting microseconds.
+pub struct FrameClock {
+    counter: CounterUs<TIM2>,
+    last_frame: u32,
+}
+
+impl FrameClock {
+    pub fn new(tim2: TIM2, clocks: &Clocks) -> FrameClock {
+        let mut counter = tim2.counter_us(clocks);
+        // Free running. TIM2 is 32 bits wide, so it takes about 71 minutes to wrap around.
+        counter.start(u32::MAX.micros()).unwrap();
+        let last_frame = counter.now().ticks();
+        FrameClock {
+            counter,
+            last_frame,
+        }
+    }
+
+    /// Microseconds since the previous call.
+    pub fn elapsed_us(&mut self) -> u32 {
+        let now = self.counter.now().ticks();
+        let elapsed = now.wrapping_sub(self.last_frame);
+        self.last_frame = now;
+        elapsed
+    }
+}
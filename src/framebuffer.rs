@@ -0,0 +1,196 @@
+use core::convert::Infallible;
+
+use embedded_dma::ReadBuffer;
+use embedded_graphics::{
+    pixelcolor::{raw::RawU16, Rgb565},
+    prelude::*,
+};
+use pong::graphics::Display;
+use st7735_lcd::ST7735;
+use stm32f4xx_hal::{
+    dma::{config::DmaConfig, traits::StreamISR, MemoryToPeripheral, Stream3, Transfer},
+    hal::{blocking::spi, digital::v2::OutputPin},
+    pac::{DMA2, SPI1},
+};
+
+pub const WIDTH: usize = 160;
+pub const HEIGHT: usize = 128;
+const ROW_BYTES: usize = WIDTH * 2;
+pub const FRAME_BYTES: usize = ROW_BYTES * HEIGHT;
+
+/// Makes what has been drawn show up on the screen.
+pub trait Flush {
+    fn flush(&mut self);
+}
+
+impl<SPI, DC, RST> Flush for Display<'_, ST7735<SPI, DC, RST>>
+where
+    SPI: spi::Write<u8>,
+    DC: OutputPin,
+    RST: OutputPin,
+{
+    fn flush(&mut self) {
+        // Everything is drawn straight to the screen.
+    }
+}
+
+impl<SPI, DC, RST> Flush for Display<'_, FrameBuffer<SPI, DC, RST>>
+where
+    SPI: spi::Write<u8>,
+    DC: OutputPin,
+    RST: OutputPin,
+{
+    fn flush(&mut self) {
+        self.display.flush();
+    }
+}
+
+/// The rows of the frame buffer being sent to the screen.
+struct FrameRows {
+    start: *const u8,
+    len: usize,
+}
+
+// The frame buffer isn't drawn on until the transfer is done, see `FrameBuffer::wait_for_flush`.
+unsafe impl ReadBuffer for FrameRows {
+    type Word = u8;
+    unsafe fn read_buffer(&self) -> (*const u8, usize) {
+        (self.start, self.len)
+    }
+}
+
+enum FlushDma {
+    Idle(Stream3<DMA2>, SPI1),
+    Busy(Transfer<Stream3<DMA2>, 3, SPI1, MemoryToPeripheral, FrameRows>),
+}
+
+/// An off-screen copy of the whole screen, 40 KiB of the F411's 128 KiB of RAM. Drawing only
+/// touches RAM, `flush` then sends the rows that changed to the ST7735 with DMA while the game
+/// goes on.
+pub struct FrameBuffer<SPI, DC, RST>
+where
+    SPI: spi::Write<u8>,
+    DC: OutputPin,
+    RST: OutputPin,
+{
+    screen: ST7735<SPI, DC, RST>,
+    pixels: &'static mut [u8; FRAME_BYTES],
+    /// First and last row drawn on since the last flush.
+    dirty_rows: Option<(usize, usize)>,
+    dma: Option<FlushDma>,
+}
+
+impl<SPI, DC, RST> FrameBuffer<SPI, DC, RST>
+where
+    SPI: spi::Write<u8>,
+    DC: OutputPin,
+    RST: OutputPin,
+{
+    /// `spi1` must be the SPI the `screen` talks through. The screen keeps sending its commands
+    /// through it, the DMA only writes pixel data to it.
+    pub fn new(
+        screen: ST7735<SPI, DC, RST>,
+        pixels: &'static mut [u8; FRAME_BYTES],
+        stream: Stream3<DMA2>,
+        spi1: SPI1,
+    ) -> Self {
+        spi1.cr2.modify(|_, w| w.txdmaen().enabled());
+        FrameBuffer {
+            screen,
+            pixels,
+            dirty_rows: Some((0, HEIGHT - 1)),
+            dma: Some(FlushDma::Idle(stream, spi1)),
+        }
+    }
+
+    /// Starts sending the rows drawn on since the last flush. Returns right away, the next
+    /// drawing waits for the transfer to finish.
+    pub fn flush(&mut self) {
+        self.wait_for_flush();
+        let (first_row, last_row) = match self.dirty_rows.take() {
+            Some(rows) => rows,
+            None => return,
+        };
+        self.screen
+            .set_address_window(0, first_row as u16, WIDTH as u16 - 1, last_row as u16)
+            .unwrap();
+        // Sends the memory write command and leaves the screen expecting pixel data.
+        self.screen.write_pixels(core::iter::empty()).unwrap();
+
+        let rows = FrameRows {
+            start: self.pixels[first_row * ROW_BYTES..].as_ptr(),
+            len: (last_row + 1 - first_row) * ROW_BYTES,
+        };
+        let mut transfer = match self.dma.take() {
+            Some(FlushDma::Idle(stream, spi1)) => Transfer::init_memory_to_peripheral(
+                stream,
+                spi1,
+                rows,
+                None,
+                DmaConfig::default().memory_increment(true),
+            ),
+            _ => unreachable!("Waited for the previous flush above"),
+        };
+        transfer.start(|_| {});
+        self.dma = Some(FlushDma::Busy(transfer));
+    }
+
+    fn wait_for_flush(&mut self) {
+        if let Some(FlushDma::Busy(transfer)) = self.dma.take() {
+            while !Stream3::<DMA2>::get_transfer_complete_flag() {}
+            let (stream, spi1, _, _) = transfer.release();
+            // The DMA is done once the last byte is handed to the SPI, it must be shifted out
+            // before the screen gets its next command.
+            while spi1.sr.read().txe().bit_is_clear() || spi1.sr.read().bsy().bit_is_set() {}
+            // Nothing reads what comes back during the transfer, clear the overrun it caused.
+            let _ = spi1.dr.read();
+            let _ = spi1.sr.read();
+            self.dma = Some(FlushDma::Idle(stream, spi1));
+        }
+    }
+
+    fn set_pixel(&mut self, x: usize, y: usize, color: Rgb565) {
+        let index = y * ROW_BYTES + x * 2;
+        // The ST7735 takes the high byte first.
+        self.pixels[index..index + 2]
+            .copy_from_slice(&RawU16::from(color).into_inner().to_be_bytes());
+        self.dirty_rows = Some(match self.dirty_rows {
+            Some((first_row, last_row)) => (first_row.min(y), last_row.max(y)),
+            None => (y, y),
+        });
+    }
+}
+
+impl<SPI, DC, RST> OriginDimensions for FrameBuffer<SPI, DC, RST>
+where
+    SPI: spi::Write<u8>,
+    DC: OutputPin,
+    RST: OutputPin,
+{
+    fn size(&self) -> Size {
+        Size::new(WIDTH as u32, HEIGHT as u32)
+    }
+}
+
+impl<SPI, DC, RST> DrawTarget for FrameBuffer<SPI, DC, RST>
+where
+    SPI: spi::Write<u8>,
+    DC: OutputPin,
+    RST: OutputPin,
+{
+    type Color = Rgb565;
+    type Error = Infallible;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        self.wait_for_flush();
+        for Pixel(point, color) in pixels.into_iter() {
+            if (0..WIDTH as i32).contains(&point.x) && (0..HEIGHT as i32).contains(&point.y) {
+                self.set_pixel(point.x as usize, point.y as usize, color);
+            }
+        }
+        Ok(())
+    }
+}
@@ -1,4 +0,0 @@
-pub mod game_objects;
-pub mod graphics;
-pub mod input;
-pub mod physics;
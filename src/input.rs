@@ -0,0 +1,116 @@
+use heapless::Vec;
+use pong::calibration::StoredCalibration;
+use pong::connection::ConnectionMonitor;
+use pong::input::{
+    InpuDirection, InputError, InputMode, LeftRightPosition, PaddleInput, PaddleRow,
+    UserInteraction,
+};
+
+use crate::adc_scan::AdcScan;
+
+/// The order the sides' controls are in the ADC's scan.
+const SIDES: [LeftRightPosition; 4] = [
+    LeftRightPosition::Left,
+    LeftRightPosition::Right,
+    LeftRightPosition::Top,
+    LeftRightPosition::Bottom,
+];
+
+/// One player's control, for the paddle in a row on a side.
+pub type Control = (LeftRightPosition, PaddleRow);
+
+/// `N` players' analog controls, one ADC channel each: left and right, then top and bottom for
+/// four players. In doubles the back paddles' controls follow in the same order.
+pub struct UserInputs<const N: usize> {
+    /// Converts all players' pins in the background.
+    pub adc: AdcScan<N>,
+    pub modes: [InputMode; N],
+    pub calibration: StoredCalibration,
+    pub connections: [ConnectionMonitor; N],
+    /// Half of the controls are for the back paddles.
+    pub doubles: bool,
+}
+
+impl<const N: usize> UserInteraction for UserInputs<N> {
+    fn get_input_direction(
+        &mut self,
+        user_position: &LeftRightPosition,
+    ) -> Result<InpuDirection, InputError> {
+        self.get_control_input(&(*user_position, PaddleRow::Front))
+            .map(|input| input.direction())
+    }
+    fn get_paddle_input(
+        &mut self,
+        user_position: &LeftRightPosition,
+    ) -> Result<PaddleInput, InputError> {
+        self.get_control_input(&(*user_position, PaddleRow::Front))
+    }
+    fn get_back_paddle_input(
+        &mut self,
+        user_position: &LeftRightPosition,
+    ) -> Result<PaddleInput, InputError> {
+        self.get_control_input(&(*user_position, PaddleRow::Back))
+    }
+}
+
+impl<const N: usize> UserInputs<N> {
+    /// The controls that are there, in the order of their channels.
+    pub fn controls(&self) -> Vec<Control, 8> {
+        let sides = self.sides();
+        (0..N)
+            .map(|channel| (SIDES[channel % sides], PaddleRow::ALL[channel / sides]))
+            .collect()
+    }
+
+    /// The latest filtered ADC reading of one of `controls()`, before calibration.
+    pub fn read_raw(&mut self, control: &Control) -> u16 {
+        self.adc.latest(self.channel_of(control))
+    }
+
+    fn get_control_input(&mut self, control: &Control) -> Result<PaddleInput, InputError> {
+        let input_percentage = self.get_input_percentage(control)?;
+        let channel = self.channel_of(control);
+        self.modes[channel].paddle_input(input_percentage)
+    }
+
+    fn get_input_percentage(&mut self, control: &Control) -> Result<u8, InputError> {
+        let channel = self.channel_of(control);
+        if channel >= N {
+            // No control was ever plugged in there.
+            return Err(InputError::Disconnected);
+        }
+        let (side, row) = control;
+        let calibration = self.calibration.get(side, row);
+        // The monitor looks at the raw readings, the filter would smooth the noise of an
+        // unplugged controller away.
+        let (sample, batch) = self.adc.read(channel);
+        let monitor = &mut self.connections[channel];
+        match batch {
+            Some(batch) => monitor.check_batch(batch, calibration)?,
+            None if !monitor.is_connected() => return Err(InputError::Disconnected),
+            None => {}
+        }
+        Ok(calibration.percentage(sample))
+    }
+
+    fn sides(&self) -> usize {
+        match self.doubles {
+            true => N / 2,
+            false => N,
+        }
+    }
+
+    fn channel_of(&self, (side, row): &Control) -> usize {
+        let side_channel = match side {
+            LeftRightPosition::Left => 0,
+            LeftRightPosition::Right => 1,
+            LeftRightPosition::Top => 2,
+            LeftRightPosition::Bottom => 3,
+        };
+        match (row, self.doubles) {
+            (PaddleRow::Back, true) => self.sides() + side_channel,
+            // Without controls of their own the back paddles follow the front ones.
+            _ => side_channel,
+        }
+    }
+}
@@ -1,25 +1,20 @@
 #![no_std]
 #![no_main]
 
-use cortex_m;
-use cortex_m::delay::Delay;
-
-use cortex_m_semihosting::hprintln;
-use game::input::LeftRightPosition;
-use game::input::TwoUserInputs;
-use game::physics::TimeTick;
-use game::physics::Velocity;
-use hal::adc::config::AdcConfig;
-use hal::gpio::Analog;
-use hal::gpio::Pin;
-use hal::hal::adc::Channel;
-use hal::hal::blocking::spi;
-use hal::hal::digital::v2::OutputPin;
-use hal::pac::ADC1;
+use hal::adc::config::{AdcConfig, Sequence};
 use hal::serial::config::WordLength;
+use input::UserInputs;
+use pong::input::LeftRightPosition;
+use pong::physics::Fixed;
+use pong::physics::FixedTimestep;
+use pong::physics::PaddleBounce;
+use pong::physics::SpeedUp;
+use pong::physics::TimeTick;
+use pong::physics::Velocity;
 use stm32f4xx_hal as hal;
 
 use hal::adc::Adc;
+use hal::dma::StreamsTuple;
 use hal::serial;
 use hal::spi::Mode;
 use hal::spi::Phase;
@@ -29,16 +24,83 @@ use panic_semihosting as _;
 use cortex_m_rt::entry;
 use embedded_graphics::{draw_target::DrawTarget, pixelcolor::Rgb565, prelude::*};
 
-use game::graphics::Display;
-use game::graphics::Graphics;
+use pong::graphics::Display;
+use pong::graphics::Graphics;
+use pong::graphics::Theme;
 use st7735_lcd::{Orientation, ST7735};
 
 use hal::prelude::*;
 
-mod game;
-use game::game_objects::*;
-use game::input;
-use input::UserInteraction;
+mod adc_scan;
+mod calibration;
+mod frame_clock;
+mod framebuffer;
+mod input;
+mod random_seed;
+use adc_scan::{AdcScan, BUFFER_SAMPLES, SAMPLE_TIME};
+use frame_clock::FrameClock;
+use framebuffer::{Flush, FrameBuffer, FRAME_BYTES};
+use pong::computer_player::{ComputerPlayer, Difficulty};
+use pong::connection::{ConnectionMonitor, DisconnectDetection};
+use pong::filter::{BatchFilter, SampleFilter};
+use pong::game_objects::*;
+use pong::input::{ButtonInput, DebouncedButton, InputMode, UserInteraction};
+use pong::random::XorShift32;
+use pong::session::{self, Session};
+use random_seed::seed_from_adc_noise;
+
+/// Length of one simulation tick. The game speed is set up per tick.
+const TICK_LENGTH_US: u32 = 20_000;
+/// Most ticks simulated between two frames, if drawing falls further behind the game slows down.
+const MAX_TICKS_PER_FRAME: u32 = 4;
+/// The ball waits a second in the middle before each serve.
+const SERVE_DELAY_TICKS: u32 = 50;
+/// Selected in the menu to begin with, `Some` to play against the computer.
+const COMPUTER_OPPONENT: Option<Difficulty> = None;
+/// Paddles on the top and bottom walls too, with their controls on PC0 and PC1.
+const FOUR_PLAYERS: bool = false;
+/// A back paddle behind each front one, with the controls of the left and right ones on PC2 and
+/// PC3, and of the top and bottom ones on PC4 and PC5.
+const DOUBLES: bool = false;
+const SIDES: usize = match FOUR_PLAYERS {
+    true => 4,
+    false => 2,
+};
+const CONTROLLERS: usize = match DOUBLES {
+    true => 2 * SIDES,
+    false => SIDES,
+};
+/// `InputMode::Joystick { dead_zone: 10 }` for sticks that move the paddle at full speed,
+/// `InputMode::AnalogStick(StickResponse { dead_zone: 10, curve: ResponseCurve::Linear })` for
+/// the paddle speed to follow how far the stick is pushed, or for knobs that set the paddle height
+/// `InputMode::Knob(KnobSmoothing::new(Fixed::from_ratio(1, 4)))`. With `DOUBLES` the back
+/// paddles' controls work like the front one on the same side.
+const LEFT_INPUT: InputMode = InputMode::Joystick { dead_zone: 10 };
+const RIGHT_INPUT: InputMode = InputMode::Joystick { dead_zone: 10 };
+/// Only used with `FOUR_PLAYERS`, up moves the paddle left.
+const TOP_INPUT: InputMode = InputMode::Joystick { dead_zone: 10 };
+const BOTTOM_INPUT: InputMode = InputMode::Joystick { dead_zone: 10 };
+/// When a controller counts as unplugged and the game waits for it, `DisconnectDetection::OFF`
+/// never stops the game.
+const DISCONNECT_DETECTION: DisconnectDetection = DisconnectDetection::DEFAULT;
+/// How each batch of readings of a control is filtered, e.g. `SampleFilter::NONE` for the newest
+/// reading as is. The median throws out spikes, smoothing across batches evens out the noise
+/// that's left but makes the paddles follow a little later.
+const INPUT_FILTER: SampleFilter = SampleFilter::new(BatchFilter::Median, Fixed::from_ratio(1, 2));
+/// Draw into a frame buffer in RAM that is sent to the screen with DMA, instead of drawing
+/// straight to the screen over blocking SPI.
+const USE_FRAMEBUFFER: bool = true;
+/// Colours to draw the game in, e.g. `Theme::HIGH_CONTRAST` or `Theme::COLOR_BLIND`.
+const THEME: Theme = Theme::CLASSIC;
+/// Lines drawn on the court, in the theme's net colour.
+const COURT: CourtMarkings = CourtMarkings {
+    center_line: true,
+    borders: false,
+    goal_zones: false,
+};
+
+static mut FRAME: [u8; FRAME_BYTES] = [0; FRAME_BYTES];
+static mut ADC_BUFFERS: [[u16; BUFFER_SAMPLES]; 3] = [[0; BUFFER_SAMPLES]; 3];
 
 #[entry]
 fn main() -> ! {
@@ -50,9 +112,11 @@ fn main() -> ! {
 
     let gpioa = dp.GPIOA.split();
 
-    /* set up UART*/
-    let mut serial_config = serial::Config::default();
-    serial_config.wordlength = WordLength::DataBits8;
+    /* set up UART, configured but not brought up yet */
+    let _serial_config = serial::Config {
+        wordlength: WordLength::DataBits8,
+        ..Default::default()
+    };
 
     /* Set up for ST7735*/
     let sck = gpioa.pa5.into_alternate::<5>();
@@ -60,9 +124,11 @@ fn main() -> ! {
     let mosi = gpioa.pa7.into_alternate::<5>();
 
     let gpiob = dp.GPIOB.split();
+    let gpioc = dp.GPIOC.split();
     let rst = gpiob.pb0.into_push_pull_output();
     let dc = gpioa.pa0.into_push_pull_output();
 
+    #[allow(clippy::useless_conversion)]
     let spi = hal::pac::SPI1::spi(
         dp.SPI1,
         (sck, miso, mosi),
@@ -74,20 +140,55 @@ fn main() -> ! {
         &clocks,
     );
 
+    let mut flash = dp.FLASH;
     let mut delay = cortex_m::delay::Delay::new(cp.SYST, clocks.hclk().to_Hz());
-    //hal::timer::Timer::new(d&p.TIM2, &clocks);
+    let frame_clock = FrameClock::new(dp.TIM2, &clocks);
 
     let x_pixels: u32 = 160;
     let y_pixels: u32 = 128;
 
     let left_player_input = gpioa.pa4.into_analog();
     let right_player_input = gpioa.pa1.into_analog();
-    let adc1 = Adc::adc1(dp.ADC1, false, AdcConfig::default());
+    let top_player_input = gpioc.pc0.into_analog();
+    let bottom_player_input = gpioc.pc1.into_analog();
+    let left_back_input = gpioc.pc2.into_analog();
+    let right_back_input = gpioc.pc3.into_analog();
+    let top_back_input = gpioc.pc4.into_analog();
+    let bottom_back_input = gpioc.pc5.into_analog();
+    let mut adc1 = Adc::adc1(dp.ADC1, false, AdcConfig::default());
+    let seed = seed_from_adc_noise(&mut adc1);
+    // Pins must be capable on analog read by ADC1, in the order of `UserInputs`.
+    adc1.configure_channel(&left_player_input, Sequence::One, SAMPLE_TIME);
+    adc1.configure_channel(&right_player_input, Sequence::Two, SAMPLE_TIME);
+    if FOUR_PLAYERS {
+        adc1.configure_channel(&top_player_input, Sequence::Three, SAMPLE_TIME);
+        adc1.configure_channel(&bottom_player_input, Sequence::Four, SAMPLE_TIME);
+    }
+    if DOUBLES {
+        let back = |side: usize| Sequence::from((SIDES + side) as u8);
+        adc1.configure_channel(&left_back_input, back(0), SAMPLE_TIME);
+        adc1.configure_channel(&right_back_input, back(1), SAMPLE_TIME);
+        if FOUR_PLAYERS {
+            adc1.configure_channel(&top_back_input, back(2), SAMPLE_TIME);
+            adc1.configure_channel(&bottom_back_input, back(3), SAMPLE_TIME);
+        }
+    }
+    let streams = StreamsTuple::new(dp.DMA2);
+    // NOTE(unsafe) main only runs once, so this is the only reference to ADC_BUFFERS.
+    let adc_buffers = unsafe { &mut *core::ptr::addr_of_mut!(ADC_BUFFERS) };
+    let adc = AdcScan::<CONTROLLERS>::new(adc1, streams.0, adc_buffers, INPUT_FILTER);
 
-    let user_input = TwoUserInputs {
-        left_user: left_player_input,
-        right_user: right_player_input,
-        adc1,
+    // The Nucleo's blue user button, pulled up on the board and low while pressed. Sampled once
+    // per frame, so a press counts after two frames, 40 ms or more.
+    let mut button = DebouncedButton::new(gpioc.pc13.into_floating_input(), true, 2);
+
+    let modes = [LEFT_INPUT, RIGHT_INPUT, TOP_INPUT, BOTTOM_INPUT];
+    let mut user_input = UserInputs {
+        adc,
+        modes: core::array::from_fn(|controller| modes[controller % SIDES]),
+        calibration: calibration::load(&flash).unwrap_or_default(),
+        connections: [ConnectionMonitor::new(DISCONNECT_DETECTION); CONTROLLERS],
+        doubles: DOUBLES,
     };
 
     let mut disp = ST7735::new(spi, dc, rst, true, false, x_pixels, y_pixels);
@@ -95,6 +196,27 @@ fn main() -> ! {
     disp.set_orientation(&Orientation::Landscape).unwrap();
     disp.clear(Rgb565::BLACK).unwrap();
 
+    // Holding the button while switching on calibrates the sticks again.
+    if button.is_down() {
+        let mut graphics = Display {
+            display: &mut disp,
+            theme: THEME,
+        };
+        graphics.set_theme(THEME);
+        let center = Point::new(x_pixels as i32 / 2, y_pixels as i32 / 2);
+        let controls = user_input.controls();
+        user_input.calibration = calibration::calibrate(
+            &mut graphics,
+            center,
+            &controls,
+            |control| user_input.read_raw(control),
+            &mut button,
+            &mut delay,
+        );
+        // Flash that can't be written only means calibrating again next time.
+        let _ = calibration::save(&mut flash, &user_input.calibration);
+    }
+
     let paddle_width = 6u32;
     let time_tick = TimeTick {
         max_ball_movement: 5,
@@ -109,49 +231,79 @@ fn main() -> ! {
             height: 40,
         })
         .time_tick(time_tick)
-        .initial_ball_velocity(Velocity { vx: 1, vy: 1 })
+        .initial_ball_velocity(Velocity::from_pixels(1, 1))
+        .paddle_bounce(PaddleBounce {
+            max_vertical_speed: 2,
+            paddle_movement_speed: 1,
+        })
+        .ball_speed_up(SpeedUp::Linear {
+            hits_per_increase: 4,
+        })
+        .serve_delay(SERVE_DELAY_TICKS)
+        .court_markings(COURT)
+        .four_players(FOUR_PLAYERS)
+        .doubles(DOUBLES)
+        .random(XorShift32::new(seed))
         .build();
+    // Not the game's seed, so that the computer's mistakes don't follow the serves.
+    let computer = ComputerPlayer::with_random(
+        LeftRightPosition::Right,
+        Difficulty::MEDIUM,
+        XorShift32::new(!seed),
+    );
+    let mode = match COMPUTER_OPPONENT {
+        Some(difficulty) => session::Mode::AgainstComputer(difficulty),
+        None => session::Mode::Humans,
+    };
+    let session = Session::new(pong, computer, mode);
 
-    let graphics = Display { display: &mut disp };
-    play(pong, graphics, user_input, delay);
+    match USE_FRAMEBUFFER {
+        true => {
+            // NOTE(unsafe) main only runs once, so this is the only reference to FRAME.
+            let pixels = unsafe { &mut *core::ptr::addr_of_mut!(FRAME) };
+            // NOTE(unsafe) the ST7735 driver owns the SPI and keeps sending its commands through
+            // it, the DMA only needs the data register in between.
+            let spi1 = unsafe { hal::pac::Peripherals::steal().SPI1 };
+            let mut frame_buffer = FrameBuffer::new(disp, pixels, streams.3, spi1);
+            let graphics = Display {
+                display: &mut frame_buffer,
+                theme: THEME,
+            };
+            play(session, graphics, user_input, button, frame_clock)
+        }
+        false => {
+            let graphics = Display {
+                display: &mut disp,
+                theme: THEME,
+            };
+            play(session, graphics, user_input, button, frame_clock)
+        }
+    }
 }
 
-// TODO: create a wrapper for the display and user input to hopefully avoid the generics.
-fn play<
-    SPI: spi::Write<u8>,
-    DC: OutputPin,
-    RST: OutputPin,
-    const PL: char,
-    const PR: char,
-    const NL: u8,
-    const NR: u8,
->(
-    mut game: Game,
-    mut display: Display<SPI, DC, RST>,
-    mut user_input: TwoUserInputs<PL, PR, NL, NR>,
-    mut delay: Delay,
-) -> !
-where
-    Pin<PL, NL, Analog>: Channel<ADC1, ID = u8>, // Pins must be capable on analog read by ADC1.
-    Pin<PR, NR, Analog>: Channel<ADC1, ID = u8>,
-{
-    let mut on_screen_objects = game.get_content_to_display();
-    game.start_new_game();
+fn play<G: Graphics + Flush, U: UserInteraction, B: ButtonInput>(
+    mut session: Session,
+    mut display: G,
+    mut user_input: U,
+    mut button: B,
+    mut frame_clock: FrameClock,
+) -> ! {
+    let mut timestep = FixedTimestep::new(TICK_LENGTH_US, MAX_TICKS_PER_FRAME);
+    display.set_theme(THEME);
+    let mut on_screen_objects = session.get_content_to_display();
+    display.draw(&on_screen_objects);
+    display.flush();
     loop {
-        display.clear(&on_screen_objects);
-        on_screen_objects = game.get_content_to_display();
-        display.draw(&on_screen_objects);
-
-        for player_side in [LeftRightPosition::Left, LeftRightPosition::Right].iter() {
-            game.move_paddle(player_side, user_input.get_input_direction(player_side));
+        let ticks = timestep.advance(frame_clock.elapsed_us());
+        if ticks == 0 {
+            continue;
         }
-        if let GameState::Finnished(winner) = game.let_ball_move() {
-            match winner {
-                GameOver::LeftWins => hprintln!("Left wins! Congratulations!"),
-                GameOver::RightWins => hprintln!("Right wins! Congratulations!"),
-            };
-            game.start_new_game();
-        }
-        delay.delay_ms(15);
+        session.update(ticks, &mut user_input, &mut button);
+
+        let content = session.get_content_to_display();
+        display.redraw(&on_screen_objects, &content);
+        on_screen_objects = content;
+        // Sending the frame overlaps with working out the next one.
+        display.flush();
     }
 }
@@ -0,0 +1,18 @@
+use stm32f4xx_hal::{
+    adc::{config::SampleTime, Adc, Temperature},
+    pac::ADC1,
+};
+
+/// A different seed every time the board is switched on, from the noise in the lowest bits of
+/// the internal temperature sensor readings.
+pub fn seed_from_adc_noise(adc: &mut Adc<ADC1>) -> u32 {
+    adc.enable_temperature_and_vref();
+    let mut seed = 0u32;
+    for _ in 0..32 {
+        // The shortest sample time gives the noisiest readings.
+        let sample = adc.convert(&Temperature, SampleTime::Cycles_3);
+        seed = seed.rotate_left(5) ^ u32::from(sample);
+    }
+    adc.disable_temperature_and_vref();
+    seed
+}